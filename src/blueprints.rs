@@ -0,0 +1,274 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::hud_ui::{House, Notice};
+use crate::player::PlayerHands;
+use crate::{
+    place_spawned, spawn_house_part, AppState, Grid, Inventory, InventoryObject, Layer, Recipe,
+    WorldObject, YSort,
+};
+
+// Hammer swings it takes to put a part up once its materials are there
+const BUILD_WORK: u32 = 4;
+// How close the player has to stand to work on a blueprint
+const REACH: f32 = 80.0;
+// Blueprints fade in from the first alpha to the second as the work goes on
+const GHOST_ALPHA: f32 = 0.4;
+const BUILT_GHOST_ALPHA: f32 = 0.8;
+
+// House part waiting to be built, nothing is used up until its materials are delivered
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
+pub struct Blueprint {
+    pub part: House,
+    pub delivered: bool,
+    pub work: u32,
+}
+
+impl Blueprint {
+    pub fn new(part: House) -> Self {
+        Self {
+            part,
+            delivered: false,
+            work: 0,
+        }
+    }
+
+    pub fn is_built(&self) -> bool {
+        self.delivered && self.work >= BUILD_WORK
+    }
+}
+
+pub struct BlueprintsPlugin;
+impl Plugin for BlueprintsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (hammer_blueprints, finish_blueprints, fade_in_blueprints)
+                .chain()
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+// Ghost of the part, walked through like it isn't there
+pub fn spawn_blueprint(
+    blueprint: Blueprint,
+    pos: Vec3,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> Entity {
+    let texture = asset_server.load(blueprint.part.texture());
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::WHITE.with_a(GHOST_ALPHA),
+                    ..default()
+                },
+                texture,
+                transform: Transform::from_translation(pos),
+                ..default()
+            },
+            WorldObject::Blueprint(blueprint.part),
+            blueprint,
+            YSort(blueprint.part.y_sort()),
+        ))
+        .id()
+}
+
+// One swing at the blueprint, the first one brings in the materials. False when they're missing
+pub fn work_on_blueprint(blueprint: &mut Blueprint, inventory: &mut Inventory) -> bool {
+    if !blueprint.delivered {
+        if !inventory.recipe_satisfied(Recipe(blueprint.part)) {
+            return false;
+        }
+        inventory.consume_recipe(Recipe(blueprint.part));
+        blueprint.delivered = true;
+        return true;
+    }
+    blueprint.work = (blueprint.work + 1).min(BUILD_WORK);
+    true
+}
+
+// Space with the hammer works on the nearest blueprint in reach
+fn hammer_blueprints(
+    input: Res<Input<KeyCode>>,
+    mut inv_query: Query<&mut Inventory>,
+    grid_query: Query<&Grid>,
+    mut hands: PlayerHands,
+    mut blueprint_query: Query<(&Transform, &mut Blueprint)>,
+    mut notices: EventWriter<Notice>,
+) {
+    let mut inventory = inv_query.single_mut();
+    if !input.just_pressed(KeyCode::Space)
+        || inventory.active_object() != Some(InventoryObject::Hammer)
+    {
+        return;
+    }
+    let grid = grid_query.single();
+    let player = hands.pos();
+    let nearest = blueprint_query
+        .iter_mut()
+        .map(|(transform, blueprint)| {
+            let distance = footprint_distance(grid, transform, blueprint.part, player);
+            (distance, blueprint)
+        })
+        .filter(|(distance, _)| *distance < REACH)
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+    let Some((_, mut blueprint)) = nearest else {
+        return;
+    };
+    if hands.too_tired() {
+        return;
+    }
+
+    if !work_on_blueprint(&mut blueprint, &mut inventory) {
+        notices.send(Notice(format!(
+            "Not enough material for {:?}",
+            blueprint.part
+        )));
+        return;
+    }
+    hands.work(InventoryObject::Hammer);
+}
+
+fn footprint_distance(grid: &Grid, transform: &Transform, part: House, pos: Vec2) -> f32 {
+    let origin = grid.world_to_grid(transform.translation.truncate());
+    part.footprint()
+        .into_iter()
+        .map(|(x, y)| {
+            grid.grid_to_world((origin.0 + x, origin.1 + y))
+                .distance(pos)
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+// Built blueprints turn solid, once nobody stands where the walls go up
+fn finish_blueprints(
+    blueprint_query: Query<(Entity, &Transform, &Blueprint)>,
+    character_query: Query<&Transform, With<KinematicCharacterController>>,
+    mut grid_query: Query<&mut Grid>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    let mut grid = grid_query.single_mut();
+    for (entity, transform, blueprint) in blueprint_query.iter() {
+        if !blueprint.is_built() {
+            continue;
+        }
+        let pos = transform.translation.truncate();
+        let origin = grid.world_to_grid(pos);
+        let footprint: Vec<(i32, i32)> = blueprint
+            .part
+            .footprint()
+            .into_iter()
+            .map(|(x, y)| (origin.0 + x, origin.1 + y))
+            .collect();
+        if character_query.iter().any(|character| {
+            footprint.contains(&grid.world_to_grid(character.translation.truncate()))
+        }) {
+            continue;
+        }
+
+        commands.entity(entity).despawn_recursive();
+        let covered = grid.grid_to_world(footprint[0]);
+        grid.remove_object(covered, Layer::Object);
+        let id = spawn_house_part(
+            blueprint.part,
+            transform.translation,
+            &mut commands,
+            &asset_server,
+        );
+        place_spawned(
+            id,
+            pos,
+            WorldObject::House(blueprint.part),
+            &mut commands,
+            &mut grid,
+        );
+    }
+}
+
+fn fade_in_blueprints(mut blueprint_query: Query<(&Blueprint, &mut Sprite), Changed<Blueprint>>) {
+    for (blueprint, mut sprite) in blueprint_query.iter_mut() {
+        let progress = blueprint.work as f32 / BUILD_WORK as f32;
+        sprite
+            .color
+            .set_a(GHOST_ALPHA + (BUILT_GHOST_ALPHA - GHOST_ALPHA) * progress);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessGame;
+
+    #[test]
+    fn click_drops_blueprint_for_workers() {
+        let mut game = HeadlessGame::new();
+        game.wait_for_assets();
+        game.inventory()
+            .items
+            .insert(InventoryObject::Wood, (false, 50));
+        game.inventory()
+            .items
+            .insert(InventoryObject::Rocks, (false, 50));
+
+        // Same as picking the part from the house buttons
+        game.pick_part(House::Door);
+        game.move_cursor((2, 3));
+        game.click(MouseButton::Left);
+
+        // Nothing is used up until the blueprint gets built
+        assert_eq!(
+            game.object_at((2, 2), Layer::Object),
+            Some(WorldObject::Blueprint(House::Door))
+        );
+        assert_eq!(game.count(InventoryObject::Wood), 50);
+
+        let built = game.step_until(1200, |game| {
+            game.object_at((2, 2), Layer::Object) == Some(WorldObject::House(House::Door))
+        });
+        assert!(built);
+        assert!(game.count(InventoryObject::Wood) < 50);
+    }
+
+    #[test]
+    fn hammer_builds_blueprint() {
+        let mut game = HeadlessGame::new();
+        game.wait_for_assets();
+        game.place(WorldObject::Blueprint(House::Wall3), (1, 1));
+        game.press(KeyCode::Key5);
+        assert_eq!(
+            game.inventory().active_object(),
+            Some(InventoryObject::Hammer)
+        );
+
+        // Without materials the hammer gets nowhere
+        game.press(KeyCode::Space);
+        game.step(1);
+        assert_eq!(
+            game.object_at((1, 0), Layer::Object),
+            Some(WorldObject::Blueprint(House::Wall3))
+        );
+
+        game.inventory()
+            .items
+            .insert(InventoryObject::Wood, (false, 1));
+        game.inventory()
+            .items
+            .insert(InventoryObject::Rocks, (false, 1));
+        // One swing delivers the materials, the rest builds
+        for _ in 0..5 {
+            game.press(KeyCode::Space);
+        }
+        game.step(1);
+        assert_eq!(
+            game.object_at((1, 0), Layer::Object),
+            Some(WorldObject::House(House::Wall3))
+        );
+        assert_eq!(game.count(InventoryObject::Wood), 0);
+        assert_eq!(game.count(InventoryObject::Rocks), 0);
+    }
+}
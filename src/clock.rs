@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{AppState, MainCamera};
+
+// Game minutes passing every real second, a day takes 12 minutes
+const MINUTES_PER_SECOND: f64 = 2.0;
+const MINUTES_PER_DAY: f64 = 24.0 * 60.0;
+// Hour the first day starts at
+const START_HOUR: f64 = 6.0;
+const DAYS_PER_SEASON: u32 = 5;
+
+const DAY_COLOR: Color = Color::rgb(0.0, 0.5, 0.0);
+const NIGHT_COLOR: Color = Color::rgb(0.0, 0.1, 0.1);
+// How dark the overlay gets in the middle of the night
+const NIGHT_ALPHA: f32 = 0.6;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    const ALL: [Season; 4] = [
+        Season::Spring,
+        Season::Summer,
+        Season::Autumn,
+        Season::Winter,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Season::Spring => "Spring",
+            Season::Summer => "Summer",
+            Season::Autumn => "Autumn",
+            Season::Winter => "Winter",
+        }
+    }
+}
+
+// In-game time, it only runs while the game is being played
+#[derive(Resource)]
+pub struct GameClock {
+    // Game minutes since midnight of the first day
+    minutes: f64,
+    // Game minutes the last frame took
+    delta: f64,
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self {
+            minutes: START_HOUR * 60.0,
+            delta: 0.0,
+        }
+    }
+}
+
+impl GameClock {
+    pub fn from_minutes(minutes: f64) -> Self {
+        Self {
+            minutes,
+            delta: 0.0,
+        }
+    }
+
+    pub fn elapsed_minutes(&self) -> f64 {
+        self.minutes
+    }
+
+    pub fn delta_minutes(&self) -> f64 {
+        self.delta
+    }
+
+    pub fn minute(&self) -> u32 {
+        (self.minutes % 60.0) as u32
+    }
+
+    pub fn hour(&self) -> u32 {
+        (self.minutes % MINUTES_PER_DAY / 60.0) as u32
+    }
+
+    // Days are counted from 1
+    pub fn day(&self) -> u32 {
+        (self.minutes / MINUTES_PER_DAY) as u32 + 1
+    }
+
+    pub fn season(&self) -> Season {
+        let index = (self.day() - 1) / DAYS_PER_SEASON;
+        Season::ALL[index as usize % Season::ALL.len()]
+    }
+
+    // Jumps ahead to START_HOUR of the coming morning
+    pub fn skip_to_morning(&mut self) {
+        let day_start = (self.minutes / MINUTES_PER_DAY).floor() * MINUTES_PER_DAY;
+        let morning = day_start + START_HOUR * 60.0;
+        self.minutes = if self.minutes < morning {
+            morning
+        } else {
+            morning + MINUTES_PER_DAY
+        };
+    }
+
+    // 1 during the day, 0 at night, dawn and dusk fade between them
+    pub fn daylight(&self) -> f32 {
+        let hour = (self.minutes % MINUTES_PER_DAY / 60.0) as f32;
+        match hour {
+            h if h < 5.0 => 0.0,
+            h if h < 7.0 => (h - 5.0) / 2.0,
+            h if h < 18.0 => 1.0,
+            h if h < 20.0 => 1.0 - (h - 18.0) / 2.0,
+            _ => 0.0,
+        }
+    }
+}
+
+// Darkens everything in the camera's view at night, the HUD stays lit
+#[derive(Component)]
+struct NightOverlay;
+
+pub struct ClockPlugin;
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameClock>()
+            .insert_resource(ClearColor(DAY_COLOR))
+            .add_systems(PostStartup, spawn_night_overlay)
+            .add_systems(
+                Update,
+                (advance_clock, tint_night)
+                    .chain()
+                    .run_if(in_state(AppState::InGame)),
+            );
+    }
+}
+
+fn spawn_night_overlay(mut commands: Commands, camera_query: Query<Entity, With<MainCamera>>) {
+    let overlay = commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: NIGHT_COLOR.with_a(0.0),
+                    custom_size: Some(Vec2::new(3000.0, 3000.0)),
+                    ..default()
+                },
+                // Just in front of the camera, above every sprite in the world
+                transform: Transform::from_xyz(0.0, 0.0, -1.0),
+                ..default()
+            },
+            NightOverlay,
+        ))
+        .id();
+    commands.entity(camera_query.single()).add_child(overlay);
+}
+
+fn advance_clock(mut clock: ResMut<GameClock>, time: Res<Time>) {
+    clock.delta = time.delta_seconds_f64() * MINUTES_PER_SECOND;
+    clock.minutes += clock.delta;
+}
+
+fn tint_night(
+    clock: Res<GameClock>,
+    mut clear_color: ResMut<ClearColor>,
+    mut overlay_query: Query<&mut Sprite, With<NightOverlay>>,
+) {
+    let darkness = 1.0 - clock.daylight();
+    clear_color.0 = Color::rgb(
+        DAY_COLOR.r() + (NIGHT_COLOR.r() - DAY_COLOR.r()) * darkness,
+        DAY_COLOR.g() + (NIGHT_COLOR.g() - DAY_COLOR.g()) * darkness,
+        DAY_COLOR.b() + (NIGHT_COLOR.b() - DAY_COLOR.b()) * darkness,
+    );
+    for mut sprite in overlay_query.iter_mut() {
+        sprite.color.set_a(darkness * NIGHT_ALPHA);
+    }
+}
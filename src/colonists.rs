@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+use crate::player::{character_bundle, CharacterAtlas};
+use crate::tasks::{Schedule, Worker};
+
+// Where the colonists show up at the start, around the player
+const START_POSITIONS: [(f32, f32); 3] = [(-75.0, -25.0), (75.0, -25.0), (-25.0, -75.0)];
+
+#[derive(Component)]
+pub struct Colonist;
+
+pub struct ColonistsPlugin;
+impl Plugin for ColonistsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostStartup, setup_colonists);
+    }
+}
+
+fn setup_colonists(mut commands: Commands, atlas: Res<CharacterAtlas>) {
+    for (x, y) in START_POSITIONS {
+        spawn_colonist(&mut commands, &atlas, Vec3::new(x, y, 0.0));
+    }
+}
+
+pub fn spawn_colonist(commands: &mut Commands, atlas: &CharacterAtlas, pos: Vec3) -> Entity {
+    commands
+        .spawn((
+            Colonist,
+            character_bundle(atlas, pos),
+            Worker::default(),
+            Schedule::default(),
+        ))
+        .id()
+}
@@ -0,0 +1,293 @@
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::clock::Season;
+use crate::ron_loader::RonLoader;
+use crate::store_ui::{Catalog, Category};
+use crate::Inventory;
+use crate::InventoryObject;
+
+const CROPS_PATH: &str = "crops.ron";
+
+// Crop item as it's named in crops.ron, its icon is the png of the same name. Interned, so the
+// item stays as cheap to copy and compare as the built-in ones
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ItemId(&'static str);
+
+impl ItemId {
+    pub fn new(id: &str) -> Self {
+        static IDS: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+        let mut ids = IDS.lock().unwrap();
+        if let Some(interned) = ids.get(id) {
+            return Self(interned);
+        }
+        let interned: &'static str = Box::leak(id.into());
+        ids.insert(interned);
+        Self(interned)
+    }
+
+    pub fn texture(&self) -> String {
+        format!("{}.png", self.0)
+    }
+
+    // "bean_pods" is shown as "Bean pods"
+    pub fn name(&self) -> String {
+        let name = self.0.replace('_', " ");
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => name,
+        }
+    }
+}
+
+impl Serialize for ItemId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|id| Self::new(&id))
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CropStage {
+    // Game minutes of growing before the stage is reached
+    pub after: f64,
+    pub texture: String,
+}
+
+#[derive(Deserialize)]
+pub struct CropDef {
+    pub seed: InventoryObject,
+    pub produce: InventoryObject,
+    pub seed_price: i32,
+    pub produce_price: i32,
+    // Produce picked from one grown crop
+    pub harvest_yield: i32,
+    // Chance of getting a seed back on harvest
+    pub seed_chance: f64,
+    // Seeds only go in the ground in these seasons, crops left over when it ends wither
+    pub seasons: Vec<Season>,
+    pub stages: Vec<CropStage>,
+}
+
+impl CropDef {
+    pub fn grows_in(&self, season: Season) -> bool {
+        self.seasons.contains(&season)
+    }
+
+    pub fn stage(&self, grown_for: f64) -> Option<&CropStage> {
+        self.stages
+            .iter()
+            .rev()
+            .find(|stage| grown_for > stage.after)
+    }
+
+    pub fn is_grown(&self, grown_for: f64) -> bool {
+        self.stages
+            .last()
+            .is_some_and(|stage| grown_for > stage.after)
+    }
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct CropDefs {
+    pub crops: Vec<CropDef>,
+}
+
+#[derive(Resource)]
+struct CropsHandle(Handle<CropDefs>);
+
+// Crop definitions, empty until the file is loaded
+#[derive(SystemParam)]
+pub struct Crops<'w> {
+    handle: Res<'w, CropsHandle>,
+    assets: Res<'w, Assets<CropDefs>>,
+}
+
+impl Crops<'_> {
+    pub fn get(&self, seed: InventoryObject) -> Option<&CropDef> {
+        self.assets
+            .get(&self.handle.0)?
+            .crops
+            .iter()
+            .find(|crop| crop.seed == seed)
+    }
+}
+
+pub struct CropsPlugin;
+impl Plugin for CropsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<CropDefs>()
+            .register_asset_loader(RonLoader::<CropDefs>::new(&["crops.ron"]))
+            .add_systems(Startup, load_crops)
+            .add_systems(Update, apply_crop_prices);
+    }
+}
+
+fn load_crops(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CropsHandle(asset_server.load(CROPS_PATH)));
+}
+
+// Store prices of seeds and produce come from the crop definitions
+fn apply_crop_prices(
+    mut events: EventReader<AssetEvent<CropDefs>>,
+    crop_defs: Res<Assets<CropDefs>>,
+    mut inv_query: Query<&mut Inventory>,
+    mut catalog: ResMut<Catalog>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        let Some(crop_defs) = crop_defs.get(*id) else {
+            continue;
+        };
+        let mut inventory = inv_query.single_mut();
+        for crop in crop_defs.crops.iter() {
+            catalog.set_price(crop.seed, crop.seed_price, Category::Seeds);
+            // Seeds sell back for half what they cost
+            inventory.prices.insert(crop.seed, crop.seed_price / 2);
+            inventory.prices.insert(crop.produce, crop.produce_price);
+            inventory.items.entry(crop.seed).or_insert((false, 0));
+            inventory.items.entry(crop.produce).or_insert((false, 0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::GameClock;
+    use crate::headless::{bean_pods, beans, HeadlessGame};
+    use crate::{Layer, WorldObject};
+
+    // Clock at 6:00 on the given day of the first year
+    fn set_day(game: &mut HeadlessGame, day: u32) {
+        let minutes = ((day - 1) * 24 * 60 + 6 * 60) as f64;
+        game.app.insert_resource(GameClock::from_minutes(minutes));
+    }
+
+    #[test]
+    fn seeds_out_of_season_arent_planted() {
+        let mut game = HeadlessGame::new();
+        game.wait_for_assets();
+        set_day(&mut game, 16);
+        assert_eq!(
+            game.app.world.resource::<GameClock>().season(),
+            Season::Winter
+        );
+        game.place(WorldObject::Flowerbed, (0, 0));
+        game.inventory().items.insert(beans(), (false, 1));
+
+        game.press(KeyCode::Key6);
+        game.press(KeyCode::Space);
+        assert_eq!(game.object_at((0, 0), Layer::Object), None);
+        assert_eq!(game.count(beans()), 1);
+    }
+
+    #[test]
+    fn crops_wither_when_season_ends() {
+        let mut game = HeadlessGame::new();
+        game.wait_for_assets();
+        set_day(&mut game, 10);
+        assert_eq!(
+            game.app.world.resource::<GameClock>().season(),
+            Season::Summer
+        );
+        game.place(WorldObject::Flowerbed, (0, 0));
+        game.inventory().items.insert(beans(), (false, 1));
+        game.press(KeyCode::Key6);
+        game.press(KeyCode::Space);
+        assert_eq!(
+            game.object_at((0, 0), Layer::Object),
+            Some(WorldObject::Crop(beans()))
+        );
+
+        // Beans don't grow in autumn, which starts the next day
+        game.skip(600.0);
+        game.step(1);
+        assert_eq!(
+            game.app.world.resource::<GameClock>().season(),
+            Season::Autumn
+        );
+        assert_eq!(
+            game.object_at((0, 0), Layer::Object),
+            Some(WorldObject::DeadCrop)
+        );
+
+        game.press(KeyCode::Key3);
+        game.press(KeyCode::Space);
+        assert_eq!(game.object_at((0, 0), Layer::Object), None);
+        assert_eq!(game.count(bean_pods()), 0);
+    }
+
+    #[test]
+    fn crop_grows_and_gets_harvested() {
+        let mut game = HeadlessGame::new();
+        game.wait_for_assets();
+        game.inventory().items.insert(beans(), (false, 1));
+
+        // The player only digs facing somewhere, one step doesn't leave the tile
+        game.press(KeyCode::S);
+        // Hotbar: Axe, Pickaxe, Hoe, Watering can, Hammer, Beans
+        game.press(KeyCode::Key3);
+        game.press(KeyCode::Space);
+        assert_eq!(
+            game.object_at((0, 0), Layer::Ground),
+            Some(WorldObject::Flowerbed)
+        );
+
+        game.press(KeyCode::Key6);
+        game.press(KeyCode::Space);
+        assert_eq!(
+            game.object_at((0, 0), Layer::Object),
+            Some(WorldObject::Crop(beans()))
+        );
+        assert_eq!(game.count(beans()), 0);
+
+        game.press(KeyCode::Key4);
+        game.press(KeyCode::Space);
+        game.skip(600.0);
+        game.press(KeyCode::Key3);
+        game.press(KeyCode::Space);
+        assert_eq!(game.object_at((0, 0), Layer::Object), None);
+        assert_eq!(game.count(bean_pods()), 2);
+    }
+
+    #[test]
+    fn dry_soil_pauses_growth() {
+        let mut game = HeadlessGame::new();
+        game.wait_for_assets();
+        game.place(WorldObject::Flowerbed, (0, 0));
+        game.inventory().items.insert(beans(), (false, 1));
+        game.press(KeyCode::Key6);
+        game.press(KeyCode::Space);
+
+        // New flowerbeds are dry, so nothing grows without watering
+        game.step(1);
+        game.skip(600.0);
+        game.press(KeyCode::Key3);
+        game.press(KeyCode::Space);
+        assert_eq!(
+            game.object_at((0, 0), Layer::Object),
+            Some(WorldObject::Crop(beans()))
+        );
+
+        game.press(KeyCode::Key4);
+        game.press(KeyCode::Space);
+        game.skip(600.0);
+        game.press(KeyCode::Key3);
+        game.press(KeyCode::Space);
+        assert_eq!(game.count(bean_pods()), 2);
+    }
+}
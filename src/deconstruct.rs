@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::blueprints::Blueprint;
+use crate::tasks::{PendingTasks, Task, TaskType};
+use crate::{
+    drop_item, AppState, Cursor, Grid, Inventory, InventoryObject, Layer, Placement, Recipe,
+    WorldObject,
+};
+
+// Hammer swings it takes to take a part down
+pub const DECONSTRUCT_WORK: u32 = 4;
+const MARKED_TINT: Color = Color::rgb(1.0, 0.6, 0.6);
+
+#[derive(Resource)]
+pub struct DeconstructConfig {
+    // Fraction of the part's recipe given back, leftover fractions of an item are left to chance
+    pub refund: f64,
+}
+
+impl Default for DeconstructConfig {
+    fn default() -> Self {
+        Self { refund: 0.5 }
+    }
+}
+
+// House part ordered to be taken down, with the swings it got so far
+#[derive(Component, Default)]
+pub struct Deconstruction {
+    pub work: u32,
+}
+
+pub struct DeconstructPlugin;
+impl Plugin for DeconstructPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DeconstructConfig>().add_systems(
+            Update,
+            (order_deconstruction, mark_deconstruction).run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+// Right click on a house part orders it taken down, on a blueprint it's called off
+fn order_deconstruction(
+    mouse: Res<Input<MouseButton>>,
+    cursor_query: Query<&Transform, With<Cursor>>,
+    mut grid_query: Query<&mut Grid>,
+    part_query: Query<(Has<Deconstruction>, Option<&Blueprint>)>,
+    mut inv_query: Query<&mut Inventory>,
+    mut pending: PendingTasks,
+    mut commands: Commands,
+) {
+    if !mouse.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let mut grid = grid_query.single_mut();
+    let pos = cursor_query.single().translation.truncate();
+    let Some(placement) = grid.get_object(pos, Layer::Object) else {
+        return;
+    };
+    let Ok((marked, blueprint)) = part_query.get(placement.entity) else {
+        return;
+    };
+    match placement.object {
+        WorldObject::House(house_part) => {
+            let task = Task::new(TaskType::Deconstruct(house_part), placement.origin);
+            // A part given up on keeps its work, ordering it again picks up from there
+            if pending.order(task) && !marked {
+                commands
+                    .entity(placement.entity)
+                    .insert(Deconstruction::default());
+            }
+        }
+        WorldObject::Blueprint(house_part) => {
+            // Materials already delivered come back in full
+            if blueprint.is_some_and(|blueprint| blueprint.delivered) {
+                let mut inventory = inv_query.single_mut();
+                let recipe = inventory.recipes.get(&Recipe(house_part)).cloned();
+                for (object, count) in recipe.unwrap_or_default() {
+                    inventory
+                        .items
+                        .entry(object)
+                        .and_modify(|(_, have)| *have += count);
+                }
+            }
+            commands.entity(placement.entity).despawn_recursive();
+            grid.remove_object(pos, Layer::Object);
+        }
+        _ => {}
+    }
+}
+
+fn mark_deconstruction(mut sprite_query: Query<&mut Sprite, Added<Deconstruction>>) {
+    for mut sprite in sprite_query.iter_mut() {
+        sprite.color = MARKED_TINT;
+    }
+}
+
+// Removes the part, part of what it was built from is dropped where it stood
+pub fn deconstruct_part(
+    part: Placement,
+    refund: f64,
+    inventory: &Inventory,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    grid: &mut Grid,
+) {
+    let WorldObject::House(house_part) = part.object else {
+        return;
+    };
+    let tiles: Vec<(i32, i32)> = house_part
+        .footprint()
+        .into_iter()
+        .map(|(x, y)| (part.origin.0 + x, part.origin.1 + y))
+        .collect();
+    commands.entity(part.entity).despawn_recursive();
+    grid.remove_object(grid.grid_to_world(tiles[0]), Layer::Object);
+
+    let mut rng = rand::thread_rng();
+    let mut items = vec![];
+    let recipe = inventory.recipes.get(&Recipe(house_part));
+    for (object, count) in recipe.into_iter().flatten() {
+        let amount = *count as f64 * refund;
+        let mut refunded = amount.floor() as i32;
+        if rng.gen_bool(amount.fract()) {
+            refunded += 1;
+        }
+        for _ in 0..refunded {
+            items.push(*object);
+        }
+    }
+    drop_items(items, &tiles, commands, asset_server, grid);
+}
+
+// One item per tile, spilling over to the tiles around once the given ones are full
+fn drop_items(
+    items: Vec<InventoryObject>,
+    tiles: &[(i32, i32)],
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    grid: &mut Grid,
+) {
+    for (item, tile) in items.into_iter().zip(tiles.iter().cycle()) {
+        let pos = grid.grid_to_world(*tile);
+        drop_item(item, pos, commands, asset_server, grid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessGame;
+    use crate::hud_ui::House;
+    use crate::tasks::{Schedule, TaskQueue, Worker};
+
+    #[test]
+    fn right_click_deconstructs_part_with_refund() {
+        let mut game = HeadlessGame::new();
+        game.wait_for_assets();
+        game.app.world.resource_mut::<DeconstructConfig>().refund = 1.0;
+        game.inventory()
+            .items
+            .insert(InventoryObject::Wood, (false, 0));
+        game.inventory()
+            .items
+            .insert(InventoryObject::Rocks, (false, 0));
+        game.place(WorldObject::House(House::Wall3), (4, 4));
+        game.move_cursor((4, 3));
+        game.click(MouseButton::Right);
+
+        let removed = game.step_until(1200, |game| game.object_at((4, 3), Layer::Object).is_none());
+        assert!(removed);
+        game.step(1);
+
+        // Refunds are either still lying around or already hauled in
+        let mut query = game.app.world.query::<&WorldObject>();
+        let dropped: Vec<WorldObject> = query.iter(&game.app.world).copied().collect();
+        for item in [InventoryObject::Wood, InventoryObject::Rocks] {
+            let lying = dropped
+                .iter()
+                .filter(|object| **object == WorldObject::Item(item))
+                .count() as i32;
+            assert_eq!(game.count(item) + lying, 1);
+        }
+    }
+
+    #[test]
+    fn ordering_again_keeps_the_work_done() {
+        let mut game = HeadlessGame::new();
+        let wall = game.place(WorldObject::House(House::Wall3), (4, 4));
+        game.move_cursor((4, 3));
+        game.click(MouseButton::Right);
+        game.app.world.get_mut::<Deconstruction>(wall).unwrap().work = 2;
+
+        game.click(MouseButton::Right);
+        assert_eq!(game.app.world.get::<Deconstruction>(wall).unwrap().work, 2);
+        // Colonists may have taken the order already
+        let task = Task::new(TaskType::Deconstruct(House::Wall3), (4, 4));
+        let mut query = game.app.world.query::<(&Worker, &Schedule)>();
+        let assigned: usize = query
+            .iter(&game.app.world)
+            .map(|(worker, schedule)| {
+                let working = usize::from(worker.task() == Some(&task));
+                working + schedule.tasks.iter().filter(|t| **t == task).count()
+            })
+            .sum();
+        let queued = game
+            .app
+            .world
+            .resource::<TaskQueue>()
+            .tasks
+            .iter()
+            .filter(|queued| **queued == task)
+            .count();
+        assert_eq!(assigned + queued, 1);
+    }
+}
@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::clock::GameClock;
+use crate::player::{AnimationTimer, Player, FRAME_SECONDS};
+use crate::rooms::Buildings;
+use crate::{AppState, Grid};
+
+pub const MAX_ENERGY: f32 = 100.0;
+// Energy one swing of a tool or one planting takes
+const ACTION_COST: f32 = 2.0;
+// Below this the player is exhausted and moves sluggishly
+const EXHAUSTED: f32 = 20.0;
+
+#[derive(Component)]
+pub struct Energy(pub f32);
+
+impl Default for Energy {
+    fn default() -> Self {
+        Self(MAX_ENERGY)
+    }
+}
+
+impl Energy {
+    pub fn is_exhausted(&self) -> bool {
+        self.0 < EXHAUSTED
+    }
+
+    // Checked right before an action, so only the ones that would happen get turned down
+    pub fn too_tired(&self) -> bool {
+        let tired = self.0 < ACTION_COST;
+        if tired {
+            info!("Too tired to work, get some sleep");
+        }
+        tired
+    }
+
+    pub fn spend(&mut self) {
+        self.0 = (self.0 - ACTION_COST).max(0.0);
+    }
+}
+
+pub struct EnergyPlugin;
+impl Plugin for EnergyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (sleep, slow_exhausted_animations).run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+// Z inside a house sleeps through to the next morning
+fn sleep(
+    input: Res<Input<KeyCode>>,
+    grid_query: Query<&Grid>,
+    mut player_query: Query<(&Transform, &mut Energy), With<Player>>,
+    mut clock: ResMut<GameClock>,
+    buildings: Buildings,
+) {
+    if !input.just_pressed(KeyCode::Z) {
+        return;
+    }
+    let grid = grid_query.single();
+    let (transform, mut energy) = player_query.single_mut();
+    if !buildings.is_indoors(grid.world_to_grid(transform.translation.truncate())) {
+        info!("Can only sleep inside a house");
+        return;
+    }
+    energy.0 = MAX_ENERGY;
+    clock.skip_to_morning();
+}
+
+fn slow_exhausted_animations(
+    mut player_query: Query<(&Energy, &mut AnimationTimer), Changed<Energy>>,
+) {
+    for (energy, mut timer) in player_query.iter_mut() {
+        let seconds = if energy.is_exhausted() {
+            FRAME_SECONDS * 2.0
+        } else {
+            FRAME_SECONDS
+        };
+        timer.set_duration(Duration::from_secs_f32(seconds));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessGame;
+    use crate::hud_ui::House;
+    use crate::{Layer, WorldObject};
+
+    #[test]
+    fn exhausted_player_cant_swing() {
+        let mut game = HeadlessGame::new();
+        game.place(WorldObject::Tree, (0, 1));
+        game.energy().0 = 3.0;
+        game.press(KeyCode::Key1);
+        for _ in 0..3 {
+            game.press(KeyCode::Space);
+        }
+        assert_eq!(
+            game.object_at((0, 1), Layer::Object),
+            Some(WorldObject::Tree)
+        );
+        assert_eq!(game.energy().0, 1.0);
+    }
+
+    #[test]
+    fn swinging_at_nothing_is_free() {
+        let mut game = HeadlessGame::new();
+        game.press(KeyCode::Key1);
+        game.press(KeyCode::Space);
+        assert_eq!(game.energy().0, MAX_ENERGY);
+    }
+
+    #[test]
+    fn sleeping_indoors_ends_the_day() {
+        let mut game = HeadlessGame::new();
+        game.build_room(House::Door);
+        game.set_player_tile((1, 0));
+        game.energy().0 = 10.0;
+
+        game.press(KeyCode::Z);
+        assert_eq!(game.energy().0, MAX_ENERGY);
+        let clock = game.app.world.resource::<GameClock>();
+        assert_eq!((clock.day(), clock.hour()), (2, 6));
+    }
+
+    #[test]
+    fn cant_sleep_outdoors() {
+        let mut game = HeadlessGame::new();
+        game.build_room(House::Door);
+        game.set_player_tile((6, 0));
+        game.energy().0 = 10.0;
+
+        game.press(KeyCode::Z);
+        assert_eq!(game.energy().0, 10.0);
+        assert_eq!(game.app.world.resource::<GameClock>().day(), 1);
+    }
+}
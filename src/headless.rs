@@ -0,0 +1,289 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::system::RunSystemOnce,
+    input::{keyboard::KeyboardInput, mouse::MouseButtonInput, ButtonState, InputPlugin},
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+use bevy_rapier2d::prelude::*;
+
+use crate::blueprints::{spawn_blueprint, Blueprint};
+use crate::clock::GameClock;
+use crate::crops::{Crops, ItemId};
+use crate::energy::Energy;
+use crate::hud_ui::{House, OnCursor};
+use crate::player::Player;
+use crate::trees::ForestConfig;
+use crate::weather::{Weather, WeatherState};
+use crate::world_gen::WorldGenConfig;
+use crate::{
+    spawn_flowerbed, spawn_grass_tile, spawn_house_part, spawn_item, spawn_rock, spawn_sapling,
+    spawn_tree, AppState, Cursor, GamePlugin, Grid, Inventory, InventoryObject, Layer, WorldObject,
+};
+
+pub fn beans() -> InventoryObject {
+    InventoryObject::Seed(ItemId::new("beans"))
+}
+
+pub fn bean_pods() -> InventoryObject {
+    InventoryObject::Produce(ItemId::new("bean_pods"))
+}
+
+// Every frame advances the clock by the same step, so runs are repeatable
+const FRAME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+// Game running without a window or renderer, driven frame by frame
+pub struct HeadlessGame {
+    pub app: App,
+}
+
+impl HeadlessGame {
+    // Starts a game on an empty map in clear weather, the player stands in the middle of tile (0, 0)
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            InputPlugin,
+            TransformPlugin,
+            HierarchyPlugin,
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0),
+        ))
+        // Textures and fonts are only handed out as handles, nothing gets drawn
+        .init_asset::<Image>()
+        .init_asset::<TextureAtlas>()
+        .init_asset::<Font>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+        .add_plugins(GamePlugin)
+        .insert_resource(WorldGenConfig {
+            tree_density: 0.0,
+            rock_density: 0.0,
+            grass_density: 0.0,
+            ..default()
+        })
+        .insert_resource(WeatherState::steady(Weather::Clear))
+        // Trees neither drop saplings nor spread unless a test asks for it
+        .insert_resource(ForestConfig {
+            sapling_chance: 0.0,
+            spread_minutes: f64::INFINITY,
+        });
+
+        let mut game = Self { app };
+        game.app
+            .world
+            .resource_mut::<NextState<AppState>>()
+            .set(AppState::InGame);
+        game.step(1);
+        game.set_player_tile((0, 0));
+        game
+    }
+
+    pub fn step(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    // Steps until the condition holds, false if it didn't within the given frames
+    pub fn step_until(&mut self, frames: usize, condition: impl Fn(&mut Self) -> bool) -> bool {
+        for _ in 0..frames {
+            if condition(self) {
+                return true;
+            }
+            self.step(1);
+        }
+        condition(self)
+    }
+
+    // Advances the game clock in one long frame
+    pub fn skip(&mut self, seconds: f32) {
+        let duration = Duration::from_secs_f32(seconds);
+        let mut time = self.app.world.resource_mut::<Time<Virtual>>();
+        let max_delta = time.max_delta();
+        // Long frames are normally cut short so the game doesn't jump after a hitch
+        time.set_max_delta(max_delta.max(duration));
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(duration));
+        self.step(1);
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME));
+        self.app
+            .world
+            .resource_mut::<Time<Virtual>>()
+            .set_max_delta(max_delta);
+    }
+
+    // Presses the key for one frame and lets go of it in the next
+    pub fn press(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Pressed);
+        self.step(1);
+        self.send_key(key, ButtonState::Released);
+        self.step(1);
+    }
+
+    pub fn hold(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Pressed);
+    }
+
+    pub fn release(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Released);
+    }
+
+    fn send_key(&mut self, key: KeyCode, state: ButtonState) {
+        self.app.world.send_event(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    pub fn click(&mut self, button: MouseButton) {
+        self.send_mouse(button, ButtonState::Pressed);
+        self.step(1);
+        self.send_mouse(button, ButtonState::Released);
+        self.step(1);
+    }
+
+    fn send_mouse(&mut self, button: MouseButton, state: ButtonState) {
+        self.app.world.send_event(MouseButtonInput {
+            button,
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    // Without a window the cursor isn't moved by the mouse, so it's put on the tile directly
+    pub fn move_cursor(&mut self, tile: (i32, i32)) {
+        let pos = self.tile_center(tile);
+        let mut query = self
+            .app
+            .world
+            .query_filtered::<&mut Transform, With<Cursor>>();
+        query.single_mut(&mut self.app.world).translation = pos.extend(0.0);
+    }
+
+    // Physics only learns where the player went in its next step, until then queries miss them
+    pub fn set_player_tile(&mut self, tile: (i32, i32)) {
+        let pos = self.tile_center(tile);
+        let mut query = self
+            .app
+            .world
+            .query_filtered::<&mut Transform, With<Player>>();
+        query.single_mut(&mut self.app.world).translation = pos.extend(0.0);
+        self.step(1);
+    }
+
+    // Spawns the object on the tile like the world generator does
+    pub fn place(&mut self, object: WorldObject, tile: (i32, i32)) -> Entity {
+        let pos = self.tile_center(tile);
+        self.app.world.run_system_once(
+            move |mut commands: Commands,
+                  asset_server: Res<AssetServer>,
+                  mut grid_query: Query<&mut Grid>,
+                  clock: Res<GameClock>| {
+                let entity = match object {
+                    WorldObject::Tree => spawn_tree(pos.extend(0.0), &mut commands, &asset_server),
+                    WorldObject::Rock => spawn_rock(pos.extend(0.0), &mut commands, &asset_server),
+                    WorldObject::Sapling => spawn_sapling(
+                        pos.extend(0.0),
+                        clock.elapsed_minutes(),
+                        &mut commands,
+                        &asset_server,
+                    ),
+                    WorldObject::Grass => {
+                        spawn_grass_tile(pos.extend(0.0), &mut commands, &asset_server)
+                    }
+                    WorldObject::Flowerbed => {
+                        spawn_flowerbed(pos.extend(0.0), &mut commands, &asset_server)
+                    }
+                    WorldObject::Item(item) => {
+                        spawn_item(item, pos.extend(0.0), &mut commands, &asset_server)
+                    }
+                    WorldObject::House(house_part) => {
+                        spawn_house_part(house_part, pos.extend(0.0), &mut commands, &asset_server)
+                    }
+                    WorldObject::Blueprint(house_part) => spawn_blueprint(
+                        Blueprint::new(house_part),
+                        pos.extend(0.0),
+                        &mut commands,
+                        &asset_server,
+                    ),
+                    WorldObject::Crop(_) | WorldObject::DeadCrop => {
+                        panic!("Crops grow in flowerbeds, they aren't placed")
+                    }
+                };
+                let placed = grid_query.single_mut().place_object(entity, pos, object);
+                assert!(placed, "{:?} doesn't fit on {:?}", object, tile);
+                entity
+            },
+        )
+    }
+
+    pub fn grid(&mut self) -> &Grid {
+        let mut query = self.app.world.query::<&Grid>();
+        query.single(&self.app.world)
+    }
+
+    pub fn object_at(&mut self, tile: (i32, i32), layer: Layer) -> Option<WorldObject> {
+        let pos = self.tile_center(tile);
+        self.grid()
+            .get_object(pos, layer)
+            .map(|placement| placement.object)
+    }
+
+    pub fn inventory(&mut self) -> Mut<'_, Inventory> {
+        let mut query = self.app.world.query::<&mut Inventory>();
+        query.single_mut(&mut self.app.world)
+    }
+
+    pub fn energy(&mut self) -> Mut<'_, Energy> {
+        let mut query = self.app.world.query_filtered::<&mut Energy, With<Player>>();
+        query.single_mut(&mut self.app.world)
+    }
+
+    pub fn count(&mut self, object: InventoryObject) -> i32 {
+        self.inventory().items[&object].1
+    }
+
+    // Crop and recipe definitions load in the background like in the game
+    pub fn wait_for_assets(&mut self) {
+        let loaded = self.step_until(600, |game| {
+            let crops_loaded = game
+                .app
+                .world
+                .run_system_once(|crops: Crops| crops.get(beans()).is_some());
+            // Loading happens on other threads, give them some time
+            std::thread::sleep(Duration::from_millis(5));
+            crops_loaded && !game.inventory().recipes.is_empty()
+        });
+        assert!(loaded, "Assets didn't load");
+    }
+
+    // Same as picking the part from the house buttons, returns the cursor holding it
+    pub fn pick_part(&mut self, part: House) -> Entity {
+        let mut query = self.app.world.query_filtered::<Entity, With<Cursor>>();
+        let cursor = query.single(&self.app.world);
+        self.app
+            .world
+            .entity_mut(cursor)
+            .insert((OnCursor(part), Sprite::default()));
+        cursor
+    }
+
+    // Corners and a wall around the tiles from (0, -1) to (4, 2), the top gap takes the door
+    pub fn build_room(&mut self, top: House) {
+        self.place(WorldObject::House(House::Corner1), (0, 0));
+        self.place(WorldObject::House(House::Corner2), (0, 3));
+        self.place(WorldObject::House(House::Corner3), (4, 3));
+        self.place(WorldObject::House(House::Corner4), (4, 0));
+        self.place(WorldObject::House(House::Wall3), (2, -1));
+        self.place(WorldObject::House(top), (2, 4));
+        self.step(1);
+    }
+
+    fn tile_center(&mut self, tile: (i32, i32)) -> Vec2 {
+        self.grid().grid_to_world(tile)
+    }
+}
@@ -1,10 +1,15 @@
 use crate::AppState;
 
 use crate::{
+    clock::GameClock,
+    energy::{Energy, MAX_ENERGY},
     menu_ui::{HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON},
+    player::Player,
+    weather::WeatherState,
     Cursor, Inventory, InventoryObject, Recipe,
 };
-use bevy::prelude::*;
+use bevy::{input::mouse::MouseWheel, prelude::*};
+use serde::{Deserialize, Serialize};
 
 #[derive(Component)]
 pub struct Hud;
@@ -12,43 +17,36 @@ pub struct Hud;
 #[derive(Component)]
 pub struct InventoryUi;
 
+// Hotbar slot showing one inventory item
 #[derive(Component)]
-pub struct WoodText;
+pub struct InventorySlot(InventoryObject);
 
 #[derive(Component)]
-pub struct RocksText;
+pub struct SlotCountText(InventoryObject);
 
 #[derive(Component)]
-pub struct BeansText;
-
-#[derive(Component)]
-pub struct PotatoText;
-
-#[derive(Component)]
-pub struct AxeButton;
-
-#[derive(Component)]
-pub struct PickaxeButton;
+pub struct StoreButton;
 
 #[derive(Component)]
-pub struct HoeButton;
+pub struct ClockText;
 
 #[derive(Component)]
-pub struct BeansButton;
+pub struct WeatherText;
 
+// Filled part of the energy bar, its width follows the player's energy
 #[derive(Component)]
-pub struct PotatoButton;
+pub struct EnergyBar;
 
 #[derive(Component)]
-pub struct StoreButton;
+pub struct NoticeText;
 
-#[derive(Component)]
-pub struct WoodUi;
+// Tells the player why what they tried didn't happen, shown in the HUD for a few seconds
+#[derive(Event)]
+pub struct Notice(pub String);
 
-#[derive(Component)]
-pub struct RockUi;
+const NOTICE_SECONDS: f32 = 3.0;
 
-#[derive(Component, PartialEq, Eq, Hash, Debug, Clone)]
+#[derive(Component, PartialEq, Eq, Hash, Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum House {
     Corner1,
     Corner2,
@@ -58,6 +56,8 @@ pub enum House {
     Wall2,
     Wall3,
     Door,
+    // Single tile of wall, its sprite follows the walls around it
+    Wall,
 }
 
 #[derive(Component, PartialEq)]
@@ -66,26 +66,22 @@ pub struct OnCursor(pub House);
 pub struct HudUiPlugin;
 impl Plugin for HudUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::InGame), spawn_hud_ui)
+        app.add_event::<Notice>()
+            .add_systems(OnEnter(AppState::InGame), spawn_hud_ui)
             .add_systems(
                 Update,
                 (
-                    update_wood_text,
-                    update_rocks_text,
-                    update_beans_text,
-                    update_potato_text,
-                    interact_with_axe_button,
-                    interact_with_pickaxe_button,
-                    interact_with_hoe_button,
-                    interact_with_beans_button,
-                    interact_with_potato_button,
+                    sync_hotbar,
+                    update_slot_count_text,
+                    interact_with_slots,
+                    select_slot_with_keys,
                     interact_with_shop_button,
                     interact_with_house_buttons,
                     color_house_buttons,
-                    spawn_wood_ui,
-                    spawn_rock_ui,
-                    spawn_beans_ui,
-                    spawn_potato_ui,
+                    update_clock_text,
+                    update_weather_text,
+                    update_energy_bar,
+                    show_notices,
                 )
                     .run_if(in_state(AppState::InGame)),
             )
@@ -93,223 +89,171 @@ impl Plugin for HudUiPlugin {
     }
 }
 
-pub fn update_wood_text(
-    mut text_query: Query<&mut Text, With<WoodText>>,
-    inv_query: Query<&Inventory>,
-) {
-    let count = inv_query.single().items[&InventoryObject::Wood].1;
-    for mut text in text_query.iter_mut() {
-        text.sections[0].value = count.to_string();
-    }
+// Items in the order they're shown in the hotbar, tools are always there
+fn hotbar_items(inventory: &Inventory) -> Vec<InventoryObject> {
+    let mut items: Vec<InventoryObject> = inventory
+        .items
+        .iter()
+        .filter(|(object, (_, count))| object.is_tool() || *count > 0)
+        .map(|(object, _)| *object)
+        .collect();
+    items.sort();
+    items
 }
 
-pub fn update_rocks_text(
-    mut text_query: Query<&mut Text, With<RocksText>>,
-    inv_query: Query<&Inventory>,
-) {
-    let count = inv_query.single().items[&InventoryObject::Rocks].1;
-    for mut text in text_query.iter_mut() {
-        text.sections[0].value = count.to_string();
+fn select_item(inventory: &mut Inventory, object: InventoryObject) {
+    for (item, (using, _)) in inventory.items.iter_mut() {
+        *using = *item == object;
     }
 }
 
-pub fn update_beans_text(
-    mut text_query: Query<&mut Text, With<BeansText>>,
-    inv_query: Query<&Inventory>,
+// Rebuilds the slots whenever an item shows up or runs out, an item that ran out is put down
+fn sync_hotbar(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    inventory_ui_query: Query<(Entity, Option<&Children>), With<InventoryUi>>,
+    slot_query: Query<&InventorySlot>,
+    mut inv_query: Query<&mut Inventory>,
 ) {
-    let count = inv_query.single().items[&InventoryObject::Beans].1;
-    for mut text in text_query.iter_mut() {
-        text.sections[0].value = count.to_string();
+    let items = hotbar_items(inv_query.single());
+    if let Some(active) = inv_query.single().active_object() {
+        if !items.contains(&active) {
+            inv_query.single_mut().items.get_mut(&active).unwrap().0 = false;
+        }
     }
-}
-
-pub fn update_potato_text(
-    mut text_query: Query<&mut Text, With<PotatoText>>,
-    inv_query: Query<&Inventory>,
-) {
-    let count = inv_query.single().items[&InventoryObject::PotatoSeeds].1;
-    for mut text in text_query.iter_mut() {
-        text.sections[0].value = count.to_string();
+    let Ok((inventory_ui, children)) = inventory_ui_query.get_single() else {
+        return;
+    };
+    let shown: Vec<InventoryObject> = children
+        .into_iter()
+        .flatten()
+        .filter_map(|child| slot_query.get(*child).ok().map(|slot| slot.0))
+        .collect();
+    if shown == items {
+        return;
     }
-}
 
-pub fn interact_with_axe_button(
-    mut button_query: Query<(&Interaction, &mut BackgroundColor), With<AxeButton>>,
-    mut inv_query: Query<&mut Inventory>,
-) {
-    if let Ok((interaction, mut background_color)) = button_query.get_single_mut() {
-        match *interaction {
-            Interaction::Pressed => {
-                *background_color = Color::WHITE.into();
-
-                for (_, (using, _)) in inv_query.single_mut().items.iter_mut() {
-                    if *using {
-                        *using = false;
-                        break;
+    commands.entity(inventory_ui).despawn_descendants();
+    commands.entity(inventory_ui).with_children(|parent| {
+        for object in items {
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            align_self: AlignSelf::FlexEnd,
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: Color::BLACK.into(),
+                        ..default()
+                    },
+                    InventorySlot(object),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Px(50.0),
+                                height: Val::Px(50.0),
+                                ..default()
+                            },
+                            background_color: Color::WHITE.into(),
+                            ..default()
+                        },
+                        UiImage::new(asset_server.load(object.texture())),
+                    ));
+                    // Tools come one of a kind, no need to count them
+                    if !object.is_tool() {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "0",
+                                TextStyle {
+                                    font_size: 30.0,
+                                    color: Color::DARK_GRAY,
+                                    ..default()
+                                },
+                            ),
+                            SlotCountText(object),
+                        ));
                     }
-                }
-
-                inv_query
-                    .single_mut()
-                    .items
-                    .entry(InventoryObject::Axe)
-                    .and_modify(|(using, _)| *using = true);
-            }
-            Interaction::Hovered => {
-                *background_color = Color::GRAY.into();
-            }
-            Interaction::None => {
-                if inv_query.single_mut().items[&InventoryObject::Axe].0 {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
+                });
         }
-    }
+    });
 }
 
-pub fn interact_with_pickaxe_button(
-    mut button_query: Query<(&Interaction, &mut BackgroundColor), With<PickaxeButton>>,
-    mut inv_query: Query<&mut Inventory>,
+fn update_slot_count_text(
+    mut text_query: Query<(&mut Text, &SlotCountText)>,
+    inv_query: Query<&Inventory>,
 ) {
-    if let Ok((interaction, mut background_color)) = button_query.get_single_mut() {
-        match *interaction {
-            Interaction::Pressed => {
-                *background_color = Color::WHITE.into();
-
-                for (_, (using, _)) in inv_query.single_mut().items.iter_mut() {
-                    if *using {
-                        *using = false;
-                        break;
-                    }
-                }
-
-                inv_query
-                    .single_mut()
-                    .items
-                    .entry(InventoryObject::Pickaxe)
-                    .and_modify(|(using, _)| *using = true);
-            }
-            Interaction::Hovered => {
-                *background_color = Color::GRAY.into();
-            }
-            Interaction::None => {
-                if inv_query.single_mut().items[&InventoryObject::Pickaxe].0 {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
-        }
+    let inventory = inv_query.single();
+    for (mut text, count_text) in text_query.iter_mut() {
+        text.sections[0].value = inventory.items[&count_text.0].1.to_string();
     }
 }
 
-pub fn interact_with_hoe_button(
-    mut button_query: Query<(&Interaction, &mut BackgroundColor), With<HoeButton>>,
+fn interact_with_slots(
+    mut button_query: Query<(&Interaction, &mut BackgroundColor, &InventorySlot)>,
     mut inv_query: Query<&mut Inventory>,
 ) {
-    if let Ok((interaction, mut background_color)) = button_query.get_single_mut() {
-        match *interaction {
-            Interaction::Pressed => {
-                *background_color = Color::WHITE.into();
-
-                for (_, (using, _)) in inv_query.single_mut().items.iter_mut() {
-                    if *using {
-                        *using = false;
-                        break;
-                    }
-                }
-
-                inv_query
-                    .single_mut()
-                    .items
-                    .entry(InventoryObject::Hoe)
-                    .and_modify(|(using, _)| *using = true);
-            }
-            Interaction::Hovered => {
-                *background_color = Color::GRAY.into();
-            }
-            Interaction::None => {
-                if inv_query.single_mut().items[&InventoryObject::Hoe].0 {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
+    for (interaction, mut background_color, slot) in button_query.iter_mut() {
+        if *interaction == Interaction::Pressed {
+            select_item(&mut inv_query.single_mut(), slot.0);
         }
+        *background_color = if inv_query.single().items[&slot.0].0 {
+            Color::WHITE.into()
+        } else if *interaction == Interaction::Hovered {
+            Color::GRAY.into()
+        } else {
+            Color::BLACK.into()
+        };
     }
 }
 
-pub fn interact_with_beans_button(
-    mut button_query: Query<(&Interaction, &mut BackgroundColor), With<BeansButton>>,
+// Keys 1-9 pick a slot, the mouse wheel steps through them
+fn select_slot_with_keys(
+    input: Res<Input<KeyCode>>,
+    mut wheel_events: EventReader<MouseWheel>,
     mut inv_query: Query<&mut Inventory>,
 ) {
-    if let Ok((interaction, mut background_color)) = button_query.get_single_mut() {
-        match *interaction {
-            Interaction::Pressed => {
-                *background_color = Color::WHITE.into();
-
-                for (_, (using, _)) in inv_query.single_mut().items.iter_mut() {
-                    if *using {
-                        *using = false;
-                        break;
-                    }
-                }
-
-                inv_query
-                    .single_mut()
-                    .items
-                    .entry(InventoryObject::Beans)
-                    .and_modify(|(using, _)| *using = true);
-            }
-            Interaction::Hovered => {
-                *background_color = Color::GRAY.into();
-            }
-            Interaction::None => {
-                if inv_query.single_mut().items[&InventoryObject::Beans].0 {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
+    let items = hotbar_items(inv_query.single());
+    if items.is_empty() {
+        return;
+    }
+    const KEYS: [KeyCode; 9] = [
+        KeyCode::Key1,
+        KeyCode::Key2,
+        KeyCode::Key3,
+        KeyCode::Key4,
+        KeyCode::Key5,
+        KeyCode::Key6,
+        KeyCode::Key7,
+        KeyCode::Key8,
+        KeyCode::Key9,
+    ];
+    let mut selected = None;
+    for (index, key) in KEYS.iter().enumerate() {
+        if input.just_pressed(*key) && index < items.len() {
+            selected = Some(items[index]);
         }
     }
-}
-
-pub fn interact_with_potato_button(
-    mut button_query: Query<(&Interaction, &mut BackgroundColor), With<PotatoButton>>,
-    mut inv_query: Query<&mut Inventory>,
-) {
-    if let Ok((interaction, mut background_color)) = button_query.get_single_mut() {
-        match *interaction {
-            Interaction::Pressed => {
-                *background_color = Color::WHITE.into();
 
-                for (_, (using, _)) in inv_query.single_mut().items.iter_mut() {
-                    if *using {
-                        *using = false;
-                        break;
-                    }
-                }
+    let scroll: f32 = wheel_events.read().map(|event| event.y).sum();
+    if scroll != 0.0 {
+        let active = inv_query.single().active_object();
+        let index = active.and_then(|active| items.iter().position(|item| *item == active));
+        let len = items.len() as i32;
+        let next = match index {
+            // Scrolling up goes left, like most hotbars
+            Some(index) if scroll > 0.0 => index as i32 - 1,
+            Some(index) => index as i32 + 1,
+            None => 0,
+        };
+        selected = Some(items[next.rem_euclid(len) as usize]);
+    }
 
-                inv_query
-                    .single_mut()
-                    .items
-                    .entry(InventoryObject::PotatoSeeds)
-                    .and_modify(|(using, _)| *using = true);
-            }
-            Interaction::Hovered => {
-                *background_color = Color::GRAY.into();
-            }
-            Interaction::None => {
-                if inv_query.single_mut().items[&InventoryObject::PotatoSeeds].0 {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
-        }
+    if let Some(object) = selected {
+        select_item(&mut inv_query.single_mut(), object);
     }
 }
 
@@ -336,129 +280,27 @@ fn interact_with_shop_button(
     }
 }
 
+// Any part can be picked up, materials are only needed once its blueprint gets built
 fn interact_with_house_buttons(
     mut button_query: Query<(&Interaction, &mut BorderColor, &House), Changed<Interaction>>,
     cursor: Query<(Entity, &Transform), With<Cursor>>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    inv_query: Query<&Inventory>,
 ) {
     for (interaction, mut border_color, house_part) in button_query.iter_mut() {
         match *interaction {
-            Interaction::Pressed => match house_part {
-                House::Corner1 => {
-                    *border_color = Color::WHITE.into();
-                    if inv_query.single().recipe_satisfied(Recipe(House::Corner1)) {
-                        let texture = asset_server.load("corner1.png");
-                        commands
-                            .entity(cursor.single().0)
-                            .insert(SpriteBundle {
-                                transform: *cursor.single().1,
-                                texture,
-                                ..default()
-                            })
-                            .insert(OnCursor(House::Corner1));
-                    }
-                }
-                House::Corner2 => {
-                    *border_color = Color::WHITE.into();
-                    if inv_query.single().recipe_satisfied(Recipe(House::Corner2)) {
-                        let texture = asset_server.load("corner2.png");
-                        commands
-                            .entity(cursor.single().0)
-                            .insert(SpriteBundle {
-                                transform: *cursor.single().1,
-                                texture,
-                                ..default()
-                            })
-                            .insert(OnCursor(House::Corner2));
-                    }
-                }
-                House::Corner3 => {
-                    *border_color = Color::WHITE.into();
-                    if inv_query.single().recipe_satisfied(Recipe(House::Corner3)) {
-                        let texture = asset_server.load("corner3.png");
-                        commands
-                            .entity(cursor.single().0)
-                            .insert(SpriteBundle {
-                                transform: *cursor.single().1,
-                                texture,
-                                ..default()
-                            })
-                            .insert(OnCursor(House::Corner3));
-                    }
-                }
-                House::Corner4 => {
-                    *border_color = Color::WHITE.into();
-                    if inv_query.single().recipe_satisfied(Recipe(House::Corner4)) {
-                        let texture = asset_server.load("corner4.png");
-                        commands
-                            .entity(cursor.single().0)
-                            .insert(SpriteBundle {
-                                transform: *cursor.single().1,
-                                texture,
-                                ..default()
-                            })
-                            .insert(OnCursor(House::Corner4));
-                    }
-                }
-                House::Wall1 => {
-                    *border_color = Color::WHITE.into();
-                    if inv_query.single().recipe_satisfied(Recipe(House::Wall1)) {
-                        let texture = asset_server.load("wall1.png");
-                        commands
-                            .entity(cursor.single().0)
-                            .insert(SpriteBundle {
-                                transform: *cursor.single().1,
-                                texture,
-                                ..default()
-                            })
-                            .insert(OnCursor(House::Wall1));
-                    }
-                }
-                House::Wall2 => {
-                    *border_color = Color::WHITE.into();
-                    if inv_query.single().recipe_satisfied(Recipe(House::Wall2)) {
-                        let texture = asset_server.load("wall2.png");
-                        commands
-                            .entity(cursor.single().0)
-                            .insert(SpriteBundle {
-                                transform: *cursor.single().1,
-                                texture,
-                                ..default()
-                            })
-                            .insert(OnCursor(House::Wall2));
-                    }
-                }
-                House::Wall3 => {
-                    *border_color = Color::WHITE.into();
-                    if inv_query.single().recipe_satisfied(Recipe(House::Wall3)) {
-                        let texture = asset_server.load("wall3.png");
-                        commands
-                            .entity(cursor.single().0)
-                            .insert(SpriteBundle {
-                                transform: *cursor.single().1,
-                                texture,
-                                ..default()
-                            })
-                            .insert(OnCursor(House::Wall3));
-                    }
-                }
-                House::Door => {
-                    *border_color = Color::WHITE.into();
-                    if inv_query.single().recipe_satisfied(Recipe(House::Door)) {
-                        let texture = asset_server.load("door.png");
-                        commands
-                            .entity(cursor.single().0)
-                            .insert(SpriteBundle {
-                                transform: *cursor.single().1,
-                                texture,
-                                ..default()
-                            })
-                            .insert(OnCursor(House::Door));
-                    }
-                }
-            },
+            Interaction::Pressed => {
+                *border_color = Color::WHITE.into();
+                let texture = asset_server.load(house_part.texture());
+                commands
+                    .entity(cursor.single().0)
+                    .insert(SpriteBundle {
+                        transform: *cursor.single().1,
+                        texture,
+                        ..default()
+                    })
+                    .insert(OnCursor(*house_part));
+            }
             Interaction::Hovered => {
                 *border_color = Color::GRAY.into();
             }
@@ -474,322 +316,70 @@ fn color_house_buttons(
     inv_query: Query<&Inventory>,
 ) {
     for (house_part, mut background_color) in button_query.iter_mut() {
-        match *house_part {
-            House::Corner1 => {
-                if inv_query.single().recipe_satisfied(Recipe(House::Corner1)) {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
-            House::Corner2 => {
-                if inv_query.single().recipe_satisfied(Recipe(House::Corner2)) {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
-            House::Corner3 => {
-                if inv_query.single().recipe_satisfied(Recipe(House::Corner3)) {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
-            House::Corner4 => {
-                if inv_query.single().recipe_satisfied(Recipe(House::Corner4)) {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
-            House::Wall1 => {
-                if inv_query.single().recipe_satisfied(Recipe(House::Wall1)) {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
-            House::Wall2 => {
-                if inv_query.single().recipe_satisfied(Recipe(House::Wall2)) {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
-            House::Wall3 => {
-                if inv_query.single().recipe_satisfied(Recipe(House::Wall3)) {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
-            House::Door => {
-                if inv_query.single().recipe_satisfied(Recipe(House::Door)) {
-                    *background_color = Color::WHITE.into();
-                } else {
-                    *background_color = Color::BLACK.into();
-                }
-            }
+        if inv_query.single().recipe_satisfied(Recipe(*house_part)) {
+            *background_color = Color::WHITE.into();
+        } else {
+            *background_color = Color::BLACK.into();
         }
     }
 }
 
-fn spawn_wood_ui(
-    mut commands: Commands,
-    inventory_ui_query: Query<Entity, With<InventoryUi>>,
-    asset_server: Res<AssetServer>,
-    object_ui_query: Query<(Entity, &WoodUi)>,
-    inventory_query: Query<&Inventory>,
-) {
-    let mut spawned = false;
-    let mut entity: Option<Entity> = None;
-    for (entity2, _) in object_ui_query.iter() {
-        spawned = true;
-        entity = Some(entity2);
-    }
-
-    if !spawned && inventory_query.single().items[&InventoryObject::Wood].1 > 0 {
-        let inventory_ui_entity = inventory_ui_query.single();
-        commands
-            .entity(inventory_ui_entity)
-            .with_children(|parent| {
-                parent
-                    .spawn(NodeBundle {
-                        style: Style {
-                            align_self: AlignSelf::FlexEnd,
-                            flex_direction: FlexDirection::Column,
-                            align_items: AlignItems::Center,
-                            ..default()
-                        },
-                        ..default()
-                    })
-                    .insert(WoodUi)
-                    .with_children(|parent| {
-                        parent.spawn((
-                            NodeBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                background_color: Color::WHITE.into(),
-                                ..default()
-                            },
-                            UiImage::new(asset_server.load("wood.png")),
-                        ));
-                        parent.spawn((
-                            TextBundle::from_section(
-                                "0",
-                                TextStyle {
-                                    font_size: 30.0,
-                                    color: Color::DARK_GRAY,
-                                    ..default()
-                                },
-                            ),
-                            WoodText,
-                        ));
-                    });
-            });
-    }
-
-    if spawned && inventory_query.single().items[&InventoryObject::Wood].1 == 0 {
-        commands.entity(entity.unwrap()).despawn_recursive();
+fn update_clock_text(clock: Res<GameClock>, mut text_query: Query<&mut Text, With<ClockText>>) {
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!(
+            "{} {}  {:02}:{:02}",
+            clock.season().name(),
+            clock.day(),
+            clock.hour(),
+            clock.minute()
+        );
     }
 }
 
-fn spawn_rock_ui(
-    mut commands: Commands,
-    inventory_ui_query: Query<Entity, With<InventoryUi>>,
-    asset_server: Res<AssetServer>,
-    object_ui_query: Query<(Entity, &RockUi)>,
-    inventory_query: Query<&Inventory>,
+fn update_weather_text(
+    weather: Res<WeatherState>,
+    mut text_query: Query<&mut Text, With<WeatherText>>,
 ) {
-    let mut spawned = false;
-    let mut entity: Option<Entity> = None;
-    for (entity2, _) in object_ui_query.iter() {
-        spawned = true;
-        entity = Some(entity2);
-    }
-
-    if !spawned && inventory_query.single().items[&InventoryObject::Rocks].1 > 0 {
-        let inventory_ui_entity = inventory_ui_query.single();
-        commands
-            .entity(inventory_ui_entity)
-            .with_children(|parent| {
-                parent
-                    .spawn(NodeBundle {
-                        style: Style {
-                            align_self: AlignSelf::FlexEnd,
-                            flex_direction: FlexDirection::Column,
-                            align_items: AlignItems::Center,
-                            ..default()
-                        },
-                        ..default()
-                    })
-                    .insert(RockUi)
-                    .with_children(|parent| {
-                        parent.spawn((
-                            NodeBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                background_color: Color::WHITE.into(),
-                                ..default()
-                            },
-                            UiImage::new(asset_server.load("rocks.png")),
-                        ));
-                        parent.spawn((
-                            TextBundle::from_section(
-                                "0",
-                                TextStyle {
-                                    font_size: 30.0,
-                                    color: Color::DARK_GRAY,
-                                    ..default()
-                                },
-                            ),
-                            RocksText,
-                        ));
-                    });
-            });
-    }
-
-    if spawned && inventory_query.single().items[&InventoryObject::Rocks].1 == 0 {
-        commands.entity(entity.unwrap()).despawn_recursive();
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!(
+            "{}, then {}",
+            weather.current.name(),
+            weather.forecast.name().to_lowercase()
+        );
     }
 }
 
-fn spawn_beans_ui(
-    mut commands: Commands,
-    inventory_ui_query: Query<Entity, With<InventoryUi>>,
-    asset_server: Res<AssetServer>,
-    object_ui_query: Query<(Entity, &BeansButton)>,
-    inventory_query: Query<&Inventory>,
+// Bar turns red once the player is exhausted
+fn update_energy_bar(
+    energy_query: Query<&Energy, With<Player>>,
+    mut bar_query: Query<(&mut Style, &mut BackgroundColor), With<EnergyBar>>,
 ) {
-    let mut spawned = false;
-    let mut entity: Option<Entity> = None;
-    for (entity2, _) in object_ui_query.iter() {
-        spawned = true;
-        entity = Some(entity2);
-    }
-
-    if !spawned && inventory_query.single().items[&InventoryObject::Beans].1 > 0 {
-        let inventory_ui_entity = inventory_ui_query.single();
-        commands
-            .entity(inventory_ui_entity)
-            .with_children(|parent| {
-                parent
-                    .spawn((
-                        ButtonBundle {
-                            style: Style {
-                                align_self: AlignSelf::FlexEnd,
-                                flex_direction: FlexDirection::Column,
-                                align_items: AlignItems::Center,
-                                ..default()
-                            },
-                            background_color: Color::BLACK.into(),
-                            ..default()
-                        },
-                        BeansButton,
-                    ))
-                    .with_children(|parent| {
-                        parent.spawn((
-                            NodeBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                background_color: Color::WHITE.into(),
-                                ..default()
-                            },
-                            UiImage::new(asset_server.load("beans.png")),
-                        ));
-                        parent.spawn((
-                            TextBundle::from_section(
-                                "0",
-                                TextStyle {
-                                    font_size: 30.0,
-                                    color: Color::DARK_GRAY,
-                                    ..default()
-                                },
-                            ),
-                            BeansText,
-                        ));
-                    });
-            });
-    }
-
-    if spawned && inventory_query.single().items[&InventoryObject::Beans].1 == 0 {
-        commands.entity(entity.unwrap()).despawn_recursive();
+    let energy = energy_query.single();
+    for (mut style, mut background_color) in bar_query.iter_mut() {
+        style.width = Val::Percent(energy.0 / MAX_ENERGY * 100.0);
+        *background_color = if energy.is_exhausted() {
+            Color::RED.into()
+        } else {
+            Color::YELLOW.into()
+        };
     }
 }
 
-fn spawn_potato_ui(
-    mut commands: Commands,
-    inventory_ui_query: Query<Entity, With<InventoryUi>>,
-    asset_server: Res<AssetServer>,
-    object_ui_query: Query<(Entity, &PotatoButton)>,
-    inventory_query: Query<&Inventory>,
+// Latest notice replaces the one up, it clears once it's been up long enough
+fn show_notices(
+    mut notices: EventReader<Notice>,
+    time: Res<Time>,
+    mut timer: Local<Timer>,
+    mut text_query: Query<&mut Text, With<NoticeText>>,
 ) {
-    let mut spawned = false;
-    let mut entity: Option<Entity> = None;
-    for (entity2, _) in object_ui_query.iter() {
-        spawned = true;
-        entity = Some(entity2);
-    }
-
-    if !spawned && inventory_query.single().items[&InventoryObject::PotatoSeeds].1 > 0 {
-        let inventory_ui_entity = inventory_ui_query.single();
-        commands
-            .entity(inventory_ui_entity)
-            .with_children(|parent| {
-                parent
-                    .spawn((
-                        ButtonBundle {
-                            style: Style {
-                                align_self: AlignSelf::FlexEnd,
-                                flex_direction: FlexDirection::Column,
-                                align_items: AlignItems::Center,
-                                ..default()
-                            },
-                            background_color: Color::BLACK.into(),
-                            ..default()
-                        },
-                        PotatoButton,
-                    ))
-                    .with_children(|parent| {
-                        parent.spawn((
-                            NodeBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                background_color: Color::WHITE.into(),
-                                ..default()
-                            },
-                            UiImage::new(asset_server.load("potato_seeds.png")),
-                        ));
-                        parent.spawn((
-                            TextBundle::from_section(
-                                "0",
-                                TextStyle {
-                                    font_size: 30.0,
-                                    color: Color::DARK_GRAY,
-                                    ..default()
-                                },
-                            ),
-                            PotatoText,
-                        ));
-                    });
-            });
-    }
-
-    if spawned && inventory_query.single().items[&InventoryObject::PotatoSeeds].1 == 0 {
-        commands.entity(entity.unwrap()).despawn_recursive();
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    if let Some(notice) = notices.read().last() {
+        text.sections[0].value = notice.0.clone();
+        *timer = Timer::from_seconds(NOTICE_SECONDS, TimerMode::Once);
+    } else if timer.tick(time.delta()).just_finished() {
+        text.sections[0].value.clear();
     }
 }
 
@@ -810,111 +400,105 @@ fn spawn_hud_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         })
         .insert(Hud)
-        // Right column
-        .with_children(|parent| {
-            parent.spawn(NodeBundle {
-                style: Style {
-                    width: Val::Percent(15.0),
-                    flex_direction: FlexDirection::Column,
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    ..default()
-                },
-                ..default()
-            });
-        })
-        // Middle column
+        // Left column
         .with_children(|parent| {
             parent
                 .spawn(NodeBundle {
                     style: Style {
-                        align_self: AlignSelf::FlexEnd,
-                        height: Val::Percent(15.0),
-                        width: Val::Percent(100.0),
-                        flex_direction: FlexDirection::Row,
-                        justify_content: JustifyContent::Center,
+                        width: Val::Percent(15.0),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::FlexStart,
                         align_items: AlignItems::Center,
+                        padding: UiRect::top(Val::Px(10.0)),
                         ..default()
                     },
                     ..default()
                 })
-                .insert(InventoryUi)
-                // Axe
                 .with_children(|parent| {
-                    parent
-                        .spawn((
-                            ButtonBundle {
-                                background_color: Color::BLACK.into(),
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 30.0,
+                                color: Color::WHITE,
                                 ..default()
                             },
-                            AxeButton,
-                        ))
-                        .with_children(|parent| {
-                            parent.spawn((
-                                NodeBundle {
-                                    style: Style {
-                                        width: Val::Px(50.0),
-                                        height: Val::Px(50.0),
-                                        ..default()
-                                    },
-                                    background_color: Color::WHITE.into(),
-                                    ..default()
-                                },
-                                UiImage::new(asset_server.load("axe.png")),
-                            ));
-                        });
-                })
-                // Pickaxe
-                .with_children(|parent| {
-                    parent
-                        .spawn((
-                            ButtonBundle {
-                                background_color: Color::BLACK.into(),
+                        ),
+                        ClockText,
+                    ));
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 20.0,
+                                color: Color::WHITE,
                                 ..default()
                             },
-                            PickaxeButton,
-                        ))
-                        .with_children(|parent| {
-                            parent.spawn((
-                                NodeBundle {
-                                    style: Style {
-                                        width: Val::Px(50.0),
-                                        height: Val::Px(50.0),
-                                        ..default()
-                                    },
-                                    background_color: Color::WHITE.into(),
-                                    ..default()
-                                },
-                                UiImage::new(asset_server.load("pickaxe.png")),
-                            ));
-                        });
-                })
-                // Hoe
-                .with_children(|parent| {
+                        ),
+                        WeatherText,
+                    ));
                     parent
-                        .spawn((
-                            ButtonBundle {
-                                background_color: Color::BLACK.into(),
+                        .spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Percent(80.0),
+                                height: Val::Px(14.0),
+                                margin: UiRect::top(Val::Px(10.0)),
+                                border: UiRect::all(Val::Px(2.0)),
                                 ..default()
                             },
-                            HoeButton,
-                        ))
+                            border_color: Color::BLACK.into(),
+                            background_color: Color::DARK_GRAY.into(),
+                            ..default()
+                        })
                         .with_children(|parent| {
                             parent.spawn((
                                 NodeBundle {
                                     style: Style {
-                                        width: Val::Px(50.0),
-                                        height: Val::Px(50.0),
+                                        width: Val::Percent(100.0),
+                                        height: Val::Percent(100.0),
                                         ..default()
                                     },
-                                    background_color: Color::WHITE.into(),
+                                    background_color: Color::YELLOW.into(),
                                     ..default()
                                 },
-                                UiImage::new(asset_server.load("hoe.png")),
+                                EnergyBar,
                             ));
                         });
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 20.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        )
+                        .with_style(Style {
+                            margin: UiRect::top(Val::Px(10.0)),
+                            ..default()
+                        }),
+                        NoticeText,
+                    ));
                 });
         })
+        // Middle column
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        align_self: AlignSelf::FlexEnd,
+                        height: Val::Percent(15.0),
+                        width: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Row,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                // Filled in by sync_hotbar
+                .insert(InventoryUi);
+        })
         // Right column
         .with_children(|parent| {
             parent
@@ -930,7 +514,7 @@ fn spawn_hud_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                     ..default()
                 })
                 .with_children(|parent| {
-                    // Wall 1
+                    // Wall
                     parent
                         .spawn((
                             ButtonBundle {
@@ -942,7 +526,7 @@ fn spawn_hud_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                                 },
                                 ..default()
                             },
-                            House::Wall1,
+                            House::Wall,
                         ))
                         .with_children(|parent| {
                             parent.spawn(ImageBundle {
@@ -951,157 +535,7 @@ fn spawn_hud_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                                     height: Val::Px(50.0),
                                     ..default()
                                 },
-                                image: UiImage::new(asset_server.load("wall1_icon.png")),
-                                ..default()
-                            });
-                        });
-                    // Wall 2
-                    parent
-                        .spawn((
-                            ButtonBundle {
-                                background_color: Color::BLACK.into(),
-                                border_color: Color::BLACK.into(),
-                                style: Style {
-                                    border: UiRect::all(Val::Px(2.0)),
-                                    ..default()
-                                },
-                                ..default()
-                            },
-                            House::Wall2,
-                        ))
-                        .with_children(|parent| {
-                            parent.spawn(ImageBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                image: UiImage::new(asset_server.load("wall2_icon.png")),
-                                ..default()
-                            });
-                        });
-                    // Wall 2
-                    parent
-                        .spawn((
-                            ButtonBundle {
-                                background_color: Color::BLACK.into(),
-                                border_color: Color::BLACK.into(),
-                                style: Style {
-                                    border: UiRect::all(Val::Px(2.0)),
-                                    ..default()
-                                },
-                                ..default()
-                            },
-                            House::Wall3,
-                        ))
-                        .with_children(|parent| {
-                            parent.spawn(ImageBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                image: UiImage::new(asset_server.load("wall3_icon.png")),
-                                ..default()
-                            });
-                        });
-                    // Corner 1
-                    parent
-                        .spawn((
-                            ButtonBundle {
-                                background_color: Color::BLACK.into(),
-                                border_color: Color::BLACK.into(),
-                                style: Style {
-                                    border: UiRect::all(Val::Px(2.0)),
-                                    ..default()
-                                },
-                                ..default()
-                            },
-                            House::Corner1,
-                        ))
-                        .with_children(|parent| {
-                            parent.spawn(ImageBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                image: UiImage::new(asset_server.load("corner1_icon.png")),
-                                ..default()
-                            });
-                        });
-                    // Corner 2
-                    parent
-                        .spawn((
-                            ButtonBundle {
-                                background_color: Color::BLACK.into(),
-                                border_color: Color::BLACK.into(),
-                                style: Style {
-                                    border: UiRect::all(Val::Px(2.0)),
-                                    ..default()
-                                },
-                                ..default()
-                            },
-                            House::Corner2,
-                        ))
-                        .with_children(|parent| {
-                            parent.spawn(ImageBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                image: UiImage::new(asset_server.load("corner2_icon.png")),
-                                ..default()
-                            });
-                        });
-                    // Corner 3
-                    parent
-                        .spawn((
-                            ButtonBundle {
-                                background_color: Color::BLACK.into(),
-                                border_color: Color::BLACK.into(),
-                                style: Style {
-                                    border: UiRect::all(Val::Px(2.0)),
-                                    ..default()
-                                },
-                                ..default()
-                            },
-                            House::Corner3,
-                        ))
-                        .with_children(|parent| {
-                            parent.spawn(ImageBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                image: UiImage::new(asset_server.load("corner3_icon.png")),
-                                ..default()
-                            });
-                        });
-                    // Corner 4
-                    parent
-                        .spawn((
-                            ButtonBundle {
-                                background_color: Color::BLACK.into(),
-                                border_color: Color::BLACK.into(),
-                                style: Style {
-                                    border: UiRect::all(Val::Px(2.0)),
-                                    ..default()
-                                },
-                                ..default()
-                            },
-                            House::Corner4,
-                        ))
-                        .with_children(|parent| {
-                            parent.spawn(ImageBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                image: UiImage::new(asset_server.load("corner4_icon.png")),
+                                image: UiImage::new(asset_server.load("wall_post.png")),
                                 ..default()
                             });
                         });
@@ -1167,3 +601,24 @@ fn spawn_hud_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 });
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{beans, HeadlessGame};
+    use crate::WorldObject;
+
+    #[test]
+    fn used_up_item_is_put_down() {
+        let mut game = HeadlessGame::new();
+        game.wait_for_assets();
+        game.inventory().items.insert(beans(), (false, 1));
+        game.place(WorldObject::Flowerbed, (0, 0));
+
+        // Hotbar: Axe, Pickaxe, Hoe, Watering can, Hammer, Beans
+        game.press(KeyCode::Key6);
+        game.press(KeyCode::Space);
+        assert_eq!(game.count(beans()), 0);
+        assert_eq!(game.inventory().active_object(), None);
+    }
+}
@@ -1,8 +1,8 @@
 /*
 TODO:
-- Make game played by group, rather than one player
 
 DONE:
+- Make game played by group, rather than one player
 - Make cursor selectable area
 - Make inventory dynamic, only show items which have instances
 - Grow plants
@@ -19,31 +19,104 @@ DONE:
 - Let camera follow the player
 - Add colisions
 */
-use std::{collections::HashMap, collections::VecDeque, time::Duration};
+use std::collections::HashMap;
 
+use bevy::ecs::system::SystemParam;
 use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_prototype_lyon::prelude::*;
 use bevy_rapier2d::prelude::*;
-use pathfinding::prelude::astar;
 use rand::Rng;
-
+use serde::{Deserialize, Serialize};
+
+mod blueprints;
+mod clock;
+mod colonists;
+mod crops;
+mod deconstruct;
+mod energy;
+#[cfg(test)]
+mod headless;
 mod hud_ui;
 mod menu_ui;
 mod player;
+mod recipes;
+mod ron_loader;
+mod rooms;
+mod save;
 mod store_ui;
-use hud_ui::{House, Hud, OnCursor};
-use player::{AnimationIndices, Movement, Player};
+mod tasks;
+mod trees;
+mod walls;
+mod weather;
+mod world_gen;
+use blueprints::{spawn_blueprint, Blueprint};
+use clock::GameClock;
+use crops::{CropDef, Crops, ItemId};
+use hud_ui::{House, Hud, Notice, OnCursor};
+use player::{Player, PlayerHands};
+use tasks::{CropGrowth, Task, TaskQueue, TaskType};
+use trees::TreeFelled;
+use walls::WallLinks;
 
 const TILE: f32 = 50.0;
 const TILE_HALF: f32 = 25.0;
+// How many tiles an item rolls over when the one it's dropped on already holds one
+const ITEM_SPILL: i32 = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Layer {
+    // Soil, grass and flowerbeds
+    Ground,
+    // Trees, rocks and crops growing in flowerbeds
+    Object,
+    // Dropped items lying on top of everything else
+    Overlay,
+}
 
 #[derive(Clone, Copy, PartialEq)]
 struct Placement {
     entity: Entity,
     grid_pos: (i32, i32),
+    // Tile the entity is anchored to, objects spanning several tiles share it
+    origin: (i32, i32),
     object: WorldObject,
 }
 
+#[derive(Default)]
+struct Tile {
+    ground: Option<Placement>,
+    object: Option<Placement>,
+    overlay: Option<Placement>,
+}
+
+impl Tile {
+    fn layer(&self, layer: Layer) -> &Option<Placement> {
+        match layer {
+            Layer::Ground => &self.ground,
+            Layer::Object => &self.object,
+            Layer::Overlay => &self.overlay,
+        }
+    }
+
+    fn layer_mut(&mut self, layer: Layer) -> &mut Option<Placement> {
+        match layer {
+            Layer::Ground => &mut self.ground,
+            Layer::Object => &mut self.object,
+            Layer::Overlay => &mut self.overlay,
+        }
+    }
+
+    fn placements(&self) -> impl Iterator<Item = &Placement> {
+        [&self.ground, &self.object, &self.overlay]
+            .into_iter()
+            .filter_map(|placement| placement.as_ref())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.ground.is_none() && self.object.is_none() && self.overlay.is_none()
+    }
+}
+
 #[derive(PartialEq)]
 struct Selection {
     entity: Entity,
@@ -53,7 +126,7 @@ struct Selection {
 #[derive(Component)]
 pub struct Grid {
     tile_size: f32,
-    placements: Vec<Placement>,
+    tiles: HashMap<(i32, i32), Tile>,
     selection: Option<Selection>,
 }
 
@@ -61,109 +134,122 @@ impl Grid {
     fn new(tile_size: f32) -> Self {
         Self {
             tile_size,
-            placements: vec![],
+            tiles: HashMap::new(),
             selection: None,
         }
     }
 
-    fn get_objects_in_selection(&self) -> Vec<Placement> {
-        let mut placements = vec![];
-        if self.selection != None {
-            let mut sel_corner1: Option<(i32, i32)> = None;
-            let mut sel_corner2: Option<(i32, i32)> = None;
-            let corners = vec![
-                self.world_to_grid(self.selection.as_ref().unwrap().pos.0),
-                self.world_to_grid(self.selection.as_ref().unwrap().pos.1),
-                (
-                    self.world_to_grid(self.selection.as_ref().unwrap().pos.0).0,
-                    self.world_to_grid(self.selection.as_ref().unwrap().pos.1).1,
-                ),
-                (
-                    self.world_to_grid(self.selection.as_ref().unwrap().pos.1).0,
-                    self.world_to_grid(self.selection.as_ref().unwrap().pos.0).1,
-                ),
-            ];
-            for corner in corners.iter() {
-                if sel_corner1 == None && sel_corner2 == None {
-                    sel_corner1 = Some(*corner);
-                    sel_corner2 = Some(*corner);
-                } else {
-                    if corner.0 <= sel_corner1.unwrap().0 && corner.1 <= sel_corner1.unwrap().1 {
-                        sel_corner1 = Some(*corner)
-                    }
-                    if corner.0 >= sel_corner2.unwrap().0 && corner.1 >= sel_corner2.unwrap().1 {
-                        sel_corner2 = Some(*corner)
-                    }
+    fn get_selected_tiles(&self) -> Vec<(i32, i32)> {
+        let mut tiles = vec![];
+        if let Some(selection) = &self.selection {
+            let corner1 = self.world_to_grid(selection.pos.0);
+            let corner2 = self.world_to_grid(selection.pos.1);
+            for x in corner1.0.min(corner2.0)..=corner1.0.max(corner2.0) {
+                for y in corner1.1.min(corner2.1)..=corner1.1.max(corner2.1) {
+                    tiles.push((x, y));
                 }
             }
-            for placement in self.placements.iter() {
-                if placement.grid_pos.0 >= sel_corner1.unwrap().0
-                    && placement.grid_pos.1 >= sel_corner1.unwrap().1
-                    && placement.grid_pos.0 <= sel_corner2.unwrap().0
-                    && placement.grid_pos.1 <= sel_corner2.unwrap().1
-                {
-                    placements.push(*placement)
+        }
+        tiles
+    }
+
+    fn remove_object(&mut self, pos: Vec2, layer: Layer) -> Option<Placement> {
+        let placement = self.get_object(pos, layer)?;
+        for (x, y) in placement.object.footprint() {
+            let grid_pos = (placement.origin.0 + x, placement.origin.1 + y);
+            if let Some(tile) = self.tiles.get_mut(&grid_pos) {
+                let slot = tile.layer_mut(layer);
+                if slot.map(|other| other.entity) == Some(placement.entity) {
+                    *slot = None;
+                }
+                if tile.is_empty() {
+                    self.tiles.remove(&grid_pos);
                 }
             }
         }
-        return placements;
+        Some(placement)
     }
 
-    fn remove_object(&mut self, pos: Vec2) {
-        let mut index: Option<usize> = None;
-        for (index2, placement) in self.placements.iter().enumerate() {
-            if self.world_to_grid(pos) == placement.grid_pos {
-                index = Some(index2);
-            }
+    fn get_object(&self, pos: Vec2, layer: Layer) -> Option<Placement> {
+        self.tiles
+            .get(&self.world_to_grid(pos))
+            .and_then(|tile| *tile.layer(layer))
+    }
+
+    // Object anchored to the origin tile, found through the first tile it covers
+    fn get_anchored(&self, origin: (i32, i32), object: WorldObject) -> Option<Placement> {
+        let (x, y) = object.footprint()[0];
+        self.tiles
+            .get(&(origin.0 + x, origin.1 + y))
+            .and_then(|tile| *tile.layer(object.layer()))
+            .filter(|placement| placement.origin == origin && placement.object == object)
+    }
+
+    fn can_place(&self, pos: Vec2, object: WorldObject) -> bool {
+        let origin = self.world_to_grid(pos);
+        object.footprint().iter().all(|(x, y)| {
+            !self
+                .tiles
+                .get(&(origin.0 + x, origin.1 + y))
+                .is_some_and(|tile| tile.layer(object.layer()).is_some())
+        })
+    }
+
+    // Claims every tile of the object's footprint, refused if any of them is taken
+    #[must_use]
+    fn place_object(&mut self, entity: Entity, pos: Vec2, object: WorldObject) -> bool {
+        if !self.can_place(pos, object) {
+            return false;
         }
-        match index {
-            Some(idx) => {
-                self.placements.remove(idx);
-            }
-            None => {}
+        let origin = self.world_to_grid(pos);
+        for (x, y) in object.footprint() {
+            let grid_pos = (origin.0 + x, origin.1 + y);
+            *self
+                .tiles
+                .entry(grid_pos)
+                .or_default()
+                .layer_mut(object.layer()) = Some(Placement {
+                entity,
+                grid_pos,
+                origin,
+                object,
+            });
         }
+        true
     }
 
-    fn get_object(&self, pos: Vec2) -> Option<Placement> {
-        for Placement {
-            entity,
-            grid_pos,
-            object,
-        } in self.placements.iter()
-        {
-            if self.world_to_grid(pos) == *grid_pos {
-                return Some(Placement {
-                    entity: *entity,
-                    grid_pos: *grid_pos,
-                    object: *object,
-                });
-            }
-        }
-        return None;
+    // Closest tile to the position with no item on it yet which can be walked up to
+    fn item_spot(&self, pos: Vec2) -> Option<Vec2> {
+        let (x, y) = self.world_to_grid(pos);
+        let mut offsets: Vec<(i32, i32)> = (-ITEM_SPILL..=ITEM_SPILL)
+            .flat_map(|dx| (-ITEM_SPILL..=ITEM_SPILL).map(move |dy| (dx, dy)))
+            .collect();
+        offsets.sort_by_key(|(dx, dy)| dx * dx + dy * dy);
+        offsets
+            .into_iter()
+            .map(|(dx, dy)| self.grid_to_world((x + dx, y + dy)))
+            .find(|tile| {
+                self.is_layer_free(*tile, Layer::Overlay)
+                    && !self
+                        .get_object(*tile, Layer::Object)
+                        .is_some_and(|placement| placement.object.blocks_movement())
+            })
     }
 
-    fn place_object(&mut self, entity: Entity, pos: Vec2, object: WorldObject) {
-        self.placements.push(Placement {
-            entity,
-            grid_pos: self.world_to_grid(pos),
-            object,
-        });
+    fn placements(&self) -> impl Iterator<Item = &Placement> {
+        self.tiles.values().flat_map(|tile| tile.placements())
+    }
+
+    fn clear(&mut self) {
+        self.tiles.clear();
     }
 
     fn is_free(&self, pos: Vec2) -> bool {
-        let mut result = true;
-        for Placement {
-            entity: _,
-            grid_pos,
-            object: _,
-        } in self.placements.iter()
-        {
-            if *grid_pos == self.world_to_grid(pos) {
-                result = false;
-                break;
-            }
-        }
-        result
+        !self.tiles.contains_key(&self.world_to_grid(pos))
+    }
+
+    fn is_layer_free(&self, pos: Vec2, layer: Layer) -> bool {
+        self.get_object(pos, layer).is_none()
     }
 
     fn world_to_grid(&self, pos: Vec2) -> (i32, i32) {
@@ -190,20 +276,38 @@ pub struct Inventory {
     coins: i32,
     items: HashMap<InventoryObject, (bool, i32)>,
     recipes: HashMap<Recipe, Vec<(InventoryObject, i32)>>,
-    costs: HashMap<InventoryObject, i32>,
+    // What the store pays for an item, items without a price can't be sold
+    prices: HashMap<InventoryObject, i32>,
 }
 
 impl Inventory {
     fn recipe_satisfied(&self, recipe: Recipe) -> bool {
+        // Nothing can be built before the recipes are loaded
+        let Some(items) = self.recipes.get(&recipe) else {
+            return false;
+        };
         let mut satisfied = true;
-        for (inventory_object, count) in &self.recipes[&recipe] {
-            if &self.items[&inventory_object].1 < count {
+        for (inventory_object, count) in items {
+            // Items never seen in the inventory count as none
+            let owned = self.items.get(inventory_object).map_or(0, |(_, n)| *n);
+            if owned < *count {
                 satisfied = false;
             }
         }
         return satisfied;
     }
 
+    fn consume_recipe(&mut self, recipe: Recipe) {
+        let Some(items) = self.recipes.get(&recipe) else {
+            return;
+        };
+        for (inventory_object, count) in items.clone() {
+            self.items
+                .entry(inventory_object)
+                .and_modify(|(_, have)| *have -= count);
+        }
+    }
+
     fn using_object(&self) -> bool {
         for (_, (using, _)) in self.items.iter() {
             if *using {
@@ -212,86 +316,225 @@ impl Inventory {
         }
         return false;
     }
+
+    fn active_object(&self) -> Option<InventoryObject> {
+        self.items
+            .iter()
+            .find(|(_, (using, _))| *using)
+            .map(|(object, _)| *object)
+    }
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-struct Pos(i32, i32);
+#[derive(Component)]
+struct MainCamera;
+
+#[derive(Component)]
+pub struct Cursor;
+
+#[derive(Component, PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+enum WorldObject {
+    House(House),
+    // House part placed but not built yet
+    Blueprint(House),
+    Tree,
+    Rock,
+    Grass,
+    Flowerbed,
+    // Crop growing in a flowerbed, named by the seed it grew from
+    Crop(InventoryObject),
+    // Young tree, grows into a full one
+    Sapling,
+    // Crop that was still in the ground when its season ended
+    DeadCrop,
+    Item(InventoryObject),
+}
 
-impl Pos {
-    fn distance(&self, other: &Pos) -> u32 {
-        (self.0.abs_diff(other.0) + self.1.abs_diff(other.1)) as u32
+impl WorldObject {
+    fn layer(&self) -> Layer {
+        match self {
+            WorldObject::Grass | WorldObject::Flowerbed => Layer::Ground,
+            WorldObject::Tree
+            | WorldObject::Rock
+            | WorldObject::House(_)
+            | WorldObject::Blueprint(_)
+            | WorldObject::Crop(_)
+            | WorldObject::Sapling
+            | WorldObject::DeadCrop => Layer::Object,
+            WorldObject::Item(_) => Layer::Overlay,
+        }
     }
 
-    fn successors(&self, grid: &Grid, goal: Pos) -> Vec<(Pos, u32)> {
-        let &Pos(x, y) = self;
-        let mut pos_vec: Vec<Pos> = vec![];
-        let pos_vec2: Vec<(i32, i32)> = vec![(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)];
-        for pos in pos_vec2.iter() {
-            if Pos(pos.0, pos.1) == goal {
-                pos_vec.push(Pos(pos.0, pos.1));
-                continue
-            }
-            let pos2 = grid.get_object(grid.grid_to_world((pos.0, pos.1)));
-            if pos2 == None {
-                pos_vec.push(Pos(pos.0, pos.1))
-            } else {
-                if !(pos2.unwrap().object == WorldObject::Tree) && !(pos2.unwrap().object == WorldObject::Rock) {
-                    pos_vec.push(Pos(pos.0, pos.1))
-                }
+    // Tiles covered by the object, relative to the tile it's placed on
+    fn footprint(&self) -> Vec<(i32, i32)> {
+        match self {
+            WorldObject::House(house_part) | WorldObject::Blueprint(house_part) => {
+                house_part.footprint()
             }
+            _ => vec![(0, 0)],
         }
+    }
 
-        pos_vec
-            .into_iter()
-            .map(|p| (p, 1))
-            .collect()
+    fn breaking_tool(&self) -> Option<InventoryObject> {
+        match self {
+            WorldObject::Tree | WorldObject::Sapling => Some(InventoryObject::Axe),
+            WorldObject::Rock => Some(InventoryObject::Pickaxe),
+            _ => None,
+        }
     }
-}
 
-struct TaskObject {
-    pos: Vec<(i32, i32)>,
+    fn blocks_movement(&self) -> bool {
+        match self {
+            WorldObject::Tree | WorldObject::Rock => true,
+            WorldObject::House(house_part) => *house_part != House::Door,
+            _ => false,
+        }
+    }
 }
 
-#[derive(PartialEq)]
-enum TaskType {
-    CutTree,
-}
+impl House {
+    // Offsets and half extents of the cuboids making up the part's collider
+    fn colliders(&self) -> Vec<(Vec2, Vec2)> {
+        match self {
+            House::Corner1 => vec![
+                (Vec2::new(-50.0, -25.0), Vec2::new(25.0, 50.0)),
+                (Vec2::new(0.0, -100.0), Vec2::new(75.0, 25.0)),
+            ],
+            House::Corner2 => vec![
+                (Vec2::new(-50.0, -75.0), Vec2::new(25.0, 50.0)),
+                (Vec2::new(0.0, 0.0), Vec2::new(75.0, 25.0)),
+            ],
+            House::Corner3 => vec![
+                (Vec2::new(50.0, -75.0), Vec2::new(25.0, 50.0)),
+                (Vec2::new(0.0, 0.0), Vec2::new(75.0, 25.0)),
+            ],
+            House::Corner4 => vec![
+                (Vec2::new(50.0, -25.0), Vec2::new(25.0, 50.0)),
+                (Vec2::new(0.0, -100.0), Vec2::new(75.0, 25.0)),
+            ],
+            House::Wall1 => vec![(Vec2::new(0.0, -50.0), Vec2::new(25.0, 75.0))],
+            House::Wall2 => vec![(Vec2::new(0.0, -50.0), Vec2::new(75.0, 25.0))],
+            House::Wall3 => vec![(Vec2::new(0.0, -50.0), Vec2::new(25.0, 25.0))],
+            // Until it's joined up to the parts around it
+            House::Wall => WallLinks::default().colliders(),
+            House::Door => vec![],
+        }
+    }
 
-struct Task {
-    task_type: TaskType,
-    task_object: TaskObject,
-}
+    fn texture(&self) -> &'static str {
+        match self {
+            House::Corner1 => "corner1.png",
+            House::Corner2 => "corner2.png",
+            House::Corner3 => "corner3.png",
+            House::Corner4 => "corner4.png",
+            House::Wall1 => "wall1.png",
+            House::Wall2 => "wall2.png",
+            House::Wall3 => "wall3.png",
+            House::Wall => "wall_post.png",
+            House::Door => "door.png",
+        }
+    }
 
-#[derive(Component)]
-struct Schedule {
-    tasks: VecDeque<Task>,
-}
+    fn y_sort(&self) -> f32 {
+        match self {
+            House::Corner1 | House::Corner4 => 250.0,
+            _ => 0.0,
+        }
+    }
 
-#[derive(Component)]
-struct MainCamera;
+    fn collider(&self) -> Collider {
+        cuboids(self.colliders())
+    }
 
-#[derive(Component)]
-pub struct Cursor;
+    fn footprint(&self) -> Vec<(i32, i32)> {
+        // Door has no collider, it only takes the tile it's walked through
+        if *self == House::Door {
+            return vec![(0, -1)];
+        }
+        let mut tiles = vec![];
+        for (offset, half) in self.colliders() {
+            let min = ((offset - half) / TILE).floor();
+            let max = ((offset + half) / TILE).ceil();
+            for x in min.x as i32..=max.x as i32 {
+                for y in min.y as i32..=max.y as i32 {
+                    let center = Vec2::new(x as f32, y as f32) * TILE;
+                    let inside = (center - offset).abs().cmplt(half).all();
+                    if inside && !tiles.contains(&(x, y)) {
+                        tiles.push((x, y));
+                    }
+                }
+            }
+        }
+        tiles
+    }
+}
 
-#[derive(Component, PartialEq, Clone, Copy)]
-enum WorldObject {
-    Tree,
-    Rock,
-    Grass,
-    Flowerbed,
-    FlowerbedWithPotatoSeeds,
-    FlowerbedWithBeans,
+// Boxes given as offset and half size, put together into one collider
+fn cuboids(boxes: Vec<(Vec2, Vec2)>) -> Collider {
+    Collider::compound(
+        boxes
+            .into_iter()
+            .map(|(offset, half)| (offset, 0.0, Collider::cuboid(half.x, half.y)))
+            .collect(),
+    )
 }
 
-#[derive(Component, PartialEq, Eq, Hash, Debug, Clone, Copy)]
+#[derive(
+    Component, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy, Serialize, Deserialize,
+)]
 enum InventoryObject {
     Axe,
     Pickaxe,
     Hoe,
+    WateringCan,
+    Hammer,
     Wood,
     Rocks,
-    Beans,
-    PotatoSeeds,
+    // Seed of a crop from crops.ron
+    Seed(ItemId),
+    // What a crop from crops.ron is harvested into
+    Produce(ItemId),
+    Sapling,
+}
+
+impl InventoryObject {
+    fn texture(&self) -> String {
+        match self {
+            InventoryObject::Axe => "axe.png".into(),
+            InventoryObject::Pickaxe => "pickaxe.png".into(),
+            InventoryObject::Hoe => "hoe.png".into(),
+            InventoryObject::WateringCan => "watering_can.png".into(),
+            InventoryObject::Hammer => "hammer.png".into(),
+            InventoryObject::Wood => "wood.png".into(),
+            InventoryObject::Rocks => "rocks.png".into(),
+            InventoryObject::Seed(id) | InventoryObject::Produce(id) => id.texture(),
+            InventoryObject::Sapling => "sapling.png".into(),
+        }
+    }
+
+    fn is_tool(&self) -> bool {
+        matches!(
+            self,
+            InventoryObject::Axe
+                | InventoryObject::Pickaxe
+                | InventoryObject::Hoe
+                | InventoryObject::WateringCan
+                | InventoryObject::Hammer
+        )
+    }
+
+    fn name(&self) -> String {
+        match self {
+            InventoryObject::Axe => "Axe".into(),
+            InventoryObject::Pickaxe => "Pickaxe".into(),
+            InventoryObject::Hoe => "Hoe".into(),
+            InventoryObject::WateringCan => "Watering can".into(),
+            InventoryObject::Hammer => "Hammer".into(),
+            InventoryObject::Wood => "Wood".into(),
+            InventoryObject::Rocks => "Rocks".into(),
+            InventoryObject::Seed(id) | InventoryObject::Produce(id) => id.name(),
+            InventoryObject::Sapling => "Sapling".into(),
+        }
+    }
 }
 
 #[derive(Component, PartialEq, Eq, Hash, Debug, Clone)]
@@ -312,15 +555,37 @@ enum AppState {
 }
 
 #[derive(Component)]
-struct GrowStartTime(Duration);
+// Game minutes on the clock when the seed was planted, pushed back while the soil is dry
+struct GrowStartTime(f64);
+
+// Game minutes a watered flowerbed takes to dry out
+const DRYING_MINUTES: f32 = 720.0;
+const DRY_SOIL_TINT: Color = Color::rgb(1.0, 0.9, 0.75);
+// Part carried on the cursor shows whether it can be dropped where it is
+const VALID_GHOST_TINT: Color = Color::rgba(0.6, 1.0, 0.6, 0.8);
+const INVALID_GHOST_TINT: Color = Color::rgba(1.0, 0.5, 0.5, 0.8);
+
+#[derive(Component)]
+// How wet a flowerbed is, from 0 for dry soil to 1 for just watered
+struct Moisture(f32);
+
+impl Moisture {
+    fn is_dry(&self) -> bool {
+        self.0 <= 0.0
+    }
+}
+
+impl GrowStartTime {
+    fn grown_for(&self, clock: &GameClock) -> f64 {
+        clock.elapsed_minutes() - self.0
+    }
+}
 
 #[derive(Event)]
 pub struct ButtonPressed;
 
 fn main() {
     App::new()
-        .insert_resource(ClearColor(Color::rgb(0.0, 0.5, 0.0)))
-        .add_state::<AppState>()
         .add_plugins((
             DefaultPlugins
                 .set(ImagePlugin::default_nearest())
@@ -336,90 +601,91 @@ fn main() {
                 .build(),
             RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0),
             // RapierDebugRenderPlugin::default(),
-            player::PlayerPlugin,
-            hud_ui::HudUiPlugin,
-            menu_ui::MenuUiPlugin,
-            store_ui::StoreUiPlugin,
             ShapePlugin,
+            GamePlugin,
         ))
-        .add_systems(Startup, setup)
-        .add_systems(PostStartup, (spawn_trees, spawn_rocks, spawn_grass))
-        .add_systems(
-            Update,
-            (
-                move_camera,
-                break_object,
-                pickup_object,
-                move_cursor,
-                drop_house_parts,
-                y_sort,
-                dig_flowerbed,
-                spread_seed,
-                grow_plants,
-                select_area,
-                select_trees,
-                do_task
-            )
-                .run_if(in_state(AppState::InGame)),
-        )
         .run();
 }
 
+// Game logic and UI state, without windowing or rendering so it also runs headless
+pub struct GamePlugin;
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<AppState>()
+            .add_plugins((
+                player::PlayerPlugin,
+                hud_ui::HudUiPlugin,
+                menu_ui::MenuUiPlugin,
+                store_ui::StoreUiPlugin,
+                save::SavePlugin,
+                world_gen::WorldGenPlugin,
+                tasks::TasksPlugin,
+                colonists::ColonistsPlugin,
+                crops::CropsPlugin,
+                recipes::RecipesPlugin,
+            ))
+            // Bevy takes at most 15 plugins at once
+            .add_plugins((
+                clock::ClockPlugin,
+                weather::WeatherPlugin,
+                trees::TreesPlugin,
+                energy::EnergyPlugin,
+                rooms::RoomsPlugin,
+                blueprints::BlueprintsPlugin,
+                deconstruct::DeconstructPlugin,
+                walls::WallsPlugin,
+            ))
+            .add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                (
+                    move_camera,
+                    break_object,
+                    pickup_object,
+                    move_cursor,
+                    tint_cursor_ghost.after(move_cursor),
+                    drop_house_parts,
+                    y_sort,
+                    dig_flowerbed,
+                    spread_seed,
+                    grow_plants,
+                    harvest_plants,
+                    wither_crops,
+                    water_plants,
+                    dry_out_soil,
+                    show_soil_moisture,
+                    select_area,
+                )
+                    .run_if(in_state(AppState::InGame)),
+            );
+    }
+}
+
 fn setup(mut commands: Commands) {
-    commands.spawn(Camera2dBundle::default()).insert(MainCamera);
+    commands
+        .spawn(Camera2dBundle::default())
+        .insert(MainCamera)
+        // Overlays drawn over the view are children of the camera, they need it to be visible
+        .insert(VisibilityBundle::default());
 
     commands.spawn(Grid::new(TILE));
 
-    commands.spawn(Schedule {
-        tasks: VecDeque::new(),
-    });
-
     commands.spawn(Inventory {
         coins: 100,
         items: HashMap::from([
             (InventoryObject::Axe, (false, 1)),
             (InventoryObject::Pickaxe, (false, 1)),
             (InventoryObject::Hoe, (false, 1)),
+            (InventoryObject::WateringCan, (false, 1)),
+            (InventoryObject::Hammer, (false, 1)),
             (InventoryObject::Wood, (false, 0)),
             (InventoryObject::Rocks, (false, 0)),
-            (InventoryObject::Beans, (false, 0)),
-            (InventoryObject::PotatoSeeds, (false, 0)),
-        ]),
-        recipes: HashMap::from([
-            (
-                Recipe(House::Corner1),
-                vec![(InventoryObject::Wood, 1), (InventoryObject::Rocks, 1)],
-            ),
-            (
-                Recipe(House::Corner2),
-                vec![(InventoryObject::Wood, 1), (InventoryObject::Rocks, 1)],
-            ),
-            (
-                Recipe(House::Corner3),
-                vec![(InventoryObject::Wood, 1), (InventoryObject::Rocks, 1)],
-            ),
-            (
-                Recipe(House::Corner4),
-                vec![(InventoryObject::Wood, 1), (InventoryObject::Rocks, 1)],
-            ),
-            (
-                Recipe(House::Wall1),
-                vec![(InventoryObject::Wood, 1), (InventoryObject::Rocks, 1)],
-            ),
-            (
-                Recipe(House::Wall2),
-                vec![(InventoryObject::Wood, 1), (InventoryObject::Rocks, 1)],
-            ),
-            (
-                Recipe(House::Wall3),
-                vec![(InventoryObject::Wood, 1), (InventoryObject::Rocks, 1)],
-            ),
-            (Recipe(House::Door), vec![(InventoryObject::Wood, 1)]),
-        ]),
-        costs: HashMap::from([
-            (InventoryObject::Beans, 20),
-            (InventoryObject::PotatoSeeds, 10),
+            (InventoryObject::Sapling, (false, 0)),
         ]),
+        // Filled in from assets/recipes.ron
+        recipes: HashMap::new(),
+        // Crop items are added and priced from assets/crops.ron
+        prices: HashMap::from([(InventoryObject::Wood, 2), (InventoryObject::Rocks, 2)]),
     });
 
     // Setup cursor
@@ -449,56 +715,6 @@ fn y_sort(mut query: Query<(&mut Transform, &YSort)>) {
     }
 }
 
-fn do_task(mut schedule_query: Query<&mut Schedule>, mut player_query: Query<&mut Transform, With<Player>>, grid_query: Query<&Grid>,) {
-    let task_option = schedule_query.single_mut().tasks.pop_front();
-    match task_option {
-        Some(task) => {
-            let goal: Pos = Pos(task.task_object.pos[0].0, task.task_object.pos[0].1);
-            let (x, y) = grid_query.single().world_to_grid(player_query.single().translation.truncate());
-            let result = astar(
-                &Pos(x, y),
-                |p| p.successors(grid_query.single(), goal),
-                |p| p.distance(&goal) / 3,
-                |p| *p == goal,
-            );
-            // println!("{:?}", result);
-            for pos in result.unwrap().0.iter() {
-                player_query.single_mut().translation = grid_query.single().grid_to_world((pos.0, pos.1)).extend(0.0);
-            }
-        }
-        None => {}
-    }
-}
-
-fn select_trees(
-    inv_query: Query<&Inventory>,
-    mut grid_query: Query<&mut Grid>,
-    mut schedule_query: Query<&mut Schedule>,
-    input: Res<Input<KeyCode>>,
-    mut commands: Commands,
-) {
-    if input.just_pressed(KeyCode::Space)
-        && inv_query.single().items[&InventoryObject::Axe].0
-        && grid_query.single().selection != None
-    {
-        for placement in grid_query.single().get_objects_in_selection() {
-            if placement.object == WorldObject::Tree {
-                // println!("{:?}", placement.grid_pos);
-                schedule_query.single_mut().tasks.push_back(Task {
-                    task_type: TaskType::CutTree,
-                    task_object: TaskObject {
-                        pos: vec![placement.grid_pos],
-                    },
-                })
-            }
-        }
-        commands
-            .entity(grid_query.single_mut().selection.as_ref().unwrap().entity)
-            .despawn();
-        grid_query.single_mut().selection = None;
-    }
-}
-
 fn select_area(
     mouse: Res<Input<MouseButton>>,
     cursor_transform: Query<&Transform, With<Cursor>>,
@@ -654,6 +870,72 @@ fn move_camera(
         .lerp(new_camera_pos, 0.2);
 }
 
+// Why a part can't be dropped where the cursor is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Misfit {
+    Taken,
+    Flowerbed,
+    InTheWay,
+}
+
+impl Misfit {
+    fn message(&self) -> &'static str {
+        match self {
+            Misfit::Taken => "Something is already built there",
+            Misfit::Flowerbed => "Can't build on a flowerbed",
+            Misfit::InTheWay => "Someone or something is in the way",
+        }
+    }
+}
+
+// None when the part's tiles are free of objects and flowerbeds, and nobody stands on them
+fn part_misfit(
+    grid: &Grid,
+    rapier_context: &RapierContext,
+    pos: Vec2,
+    house_part: House,
+) -> Option<Misfit> {
+    let object = WorldObject::House(house_part);
+    if !grid.can_place(pos, object) {
+        return Some(Misfit::Taken);
+    }
+    let origin = grid.world_to_grid(pos);
+    // A bit smaller than the tile so touching the neighbours doesn't count
+    let shape = Collider::cuboid(TILE_HALF - 1.0, TILE_HALF - 1.0);
+    for (x, y) in object.footprint() {
+        let tile_pos = grid.grid_to_world((origin.0 + x, origin.1 + y));
+        let ground = grid.get_object(tile_pos, Layer::Ground);
+        if ground.map(|placement| placement.object) == Some(WorldObject::Flowerbed) {
+            return Some(Misfit::Flowerbed);
+        }
+        if rapier_context
+            .intersection_with_shape(tile_pos, 0.0, &shape, QueryFilter::default())
+            .is_some()
+        {
+            return Some(Misfit::InTheWay);
+        }
+    }
+    None
+}
+
+fn tint_cursor_ghost(
+    mut cursor_query: Query<(&Transform, &OnCursor, &mut Sprite), With<Cursor>>,
+    grid_query: Query<&Grid>,
+    rapier_context: Res<RapierContext>,
+) {
+    let Ok((transform, on_cursor, mut sprite)) = cursor_query.get_single_mut() else {
+        return;
+    };
+    let pos = transform.translation.truncate();
+    let misfit = part_misfit(grid_query.single(), &rapier_context, pos, on_cursor.0);
+    sprite.color = if misfit.is_none() {
+        VALID_GHOST_TINT
+    } else {
+        INVALID_GHOST_TINT
+    };
+}
+
+// Clicking leaves a blueprint of the part, building it is queued for the workers
 fn drop_house_parts(
     mut commands: Commands,
     mouse: Res<Input<MouseButton>>,
@@ -661,275 +943,38 @@ fn drop_house_parts(
     cursor: Query<(Entity, &Transform), With<Cursor>>,
     cursor2: Query<&OnCursor, With<Cursor>>,
     sprite_query: Query<&Sprite>,
-    mut inv_query: Query<&mut Inventory>,
-    button_query: Query<&House>,
+    mut grid_query: Query<&mut Grid>,
+    mut queue: ResMut<TaskQueue>,
+    rapier_context: Res<RapierContext>,
+    mut notices: EventWriter<Notice>,
 ) {
     if sprite_query.contains(cursor.single().0) {
         if mouse.just_pressed(MouseButton::Left) {
-            commands.entity(cursor.single().0).remove::<Sprite>();
-
-            for house_part in button_query.iter() {
-                // Corner 1
-                if *cursor2.single() == OnCursor(House::Corner1)
-                    && *house_part == House::Corner1
-                    && inv_query.single().recipe_satisfied(Recipe(House::Corner1))
-                {
-                    let texture = asset_server.load("corner1.png");
-
-                    commands.spawn((
-                        SpriteBundle {
-                            transform: *cursor.single().1,
-                            texture,
-                            ..default()
-                        },
-                        Collider::compound(vec![
-                            (Vect::new(-50.0, -25.0), 0.0, Collider::cuboid(25.0, 50.0)),
-                            (Vect::new(0.0, -100.0), 0.0, Collider::cuboid(75.0, 25.0)),
-                        ]),
-                        YSort(250.0),
-                    ));
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Rocks)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Wood)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    commands.entity(cursor.single().0).remove::<OnCursor>();
-                }
-                // Corner 2
-                if *cursor2.single() == OnCursor(House::Corner2)
-                    && *house_part == House::Corner2
-                    && inv_query.single().recipe_satisfied(Recipe(House::Corner2))
-                {
-                    let texture = asset_server.load("corner2.png");
-
-                    commands.spawn((
-                        SpriteBundle {
-                            transform: *cursor.single().1,
-                            texture,
-                            ..default()
-                        },
-                        Collider::compound(vec![
-                            (Vect::new(-50.0, -75.0), 0.0, Collider::cuboid(25.0, 50.0)),
-                            (Vect::new(0.0, 0.0), 0.0, Collider::cuboid(75.0, 25.0)),
-                        ]),
-                        YSort(0.0),
-                    ));
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Rocks)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Wood)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    commands.entity(cursor.single().0).remove::<OnCursor>();
-                }
-                // Corner 3
-                if *cursor2.single() == OnCursor(House::Corner3)
-                    && *house_part == House::Corner3
-                    && inv_query.single().recipe_satisfied(Recipe(House::Corner3))
-                {
-                    let texture = asset_server.load("corner3.png");
-
-                    commands.spawn((
-                        SpriteBundle {
-                            transform: *cursor.single().1,
-                            texture,
-                            ..default()
-                        },
-                        Collider::compound(vec![
-                            (Vect::new(50.0, -75.0), 0.0, Collider::cuboid(25.0, 50.0)),
-                            (Vect::new(0.0, 0.0), 0.0, Collider::cuboid(75.0, 25.0)),
-                        ]),
-                        YSort(0.0),
-                    ));
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Rocks)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Wood)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    commands.entity(cursor.single().0).remove::<OnCursor>();
-                }
-                // Corner 4
-                if *cursor2.single() == OnCursor(House::Corner4)
-                    && *house_part == House::Corner4
-                    && inv_query.single().recipe_satisfied(Recipe(House::Corner4))
-                {
-                    let texture = asset_server.load("corner4.png");
-
-                    commands.spawn((
-                        SpriteBundle {
-                            transform: *cursor.single().1,
-                            texture,
-                            ..default()
-                        },
-                        Collider::compound(vec![
-                            (Vect::new(50.0, -25.0), 0.0, Collider::cuboid(25.0, 50.0)),
-                            (Vect::new(0.0, -100.0), 0.0, Collider::cuboid(75.0, 25.0)),
-                        ]),
-                        YSort(250.0),
-                    ));
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Rocks)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Wood)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    commands.entity(cursor.single().0).remove::<OnCursor>();
-                }
-                // Wall 1
-                if *cursor2.single() == OnCursor(House::Wall1)
-                    && *house_part == House::Wall1
-                    && inv_query.single().recipe_satisfied(Recipe(House::Wall1))
-                {
-                    let texture = asset_server.load("wall1.png");
-                    commands.spawn((
-                        SpriteBundle {
-                            transform: *cursor.single().1,
-                            texture,
-                            ..default()
-                        },
-                        Collider::compound(vec![(
-                            Vect::new(0.0, -50.0),
-                            0.0,
-                            Collider::cuboid(25.0, 75.0),
-                        )]),
-                        YSort(0.0),
-                    ));
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Rocks)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Wood)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    commands.entity(cursor.single().0).remove::<OnCursor>();
-                }
-                // Wall 2
-                if *cursor2.single() == OnCursor(House::Wall2)
-                    && *house_part == House::Wall2
-                    && inv_query.single().recipe_satisfied(Recipe(House::Wall2))
-                {
-                    let texture = asset_server.load("wall2.png");
-                    commands.spawn((
-                        SpriteBundle {
-                            transform: *cursor.single().1,
-                            texture,
-                            ..default()
-                        },
-                        Collider::compound(vec![(
-                            Vect::new(0.0, -50.0),
-                            0.0,
-                            Collider::cuboid(75.0, 25.0),
-                        )]),
-                        YSort(0.0),
-                    ));
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Rocks)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Wood)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    commands.entity(cursor.single().0).remove::<OnCursor>();
-                }
-                // Wall 3
-                if *cursor2.single() == OnCursor(House::Wall3)
-                    && *house_part == House::Wall3
-                    && inv_query.single().recipe_satisfied(Recipe(House::Wall3))
-                {
-                    let texture = asset_server.load("wall3.png");
-                    commands.spawn((
-                        SpriteBundle {
-                            transform: *cursor.single().1,
-                            texture,
-                            ..default()
-                        },
-                        Collider::compound(vec![(
-                            Vect::new(0.0, -50.0),
-                            0.0,
-                            Collider::cuboid(25.0, 25.0),
-                        )]),
-                        YSort(0.0),
-                    ));
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Rocks)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Wood)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    commands.entity(cursor.single().0).remove::<OnCursor>();
-                }
-                // Door
-                if *cursor2.single() == OnCursor(House::Door)
-                    && *house_part == House::Door
-                    && inv_query.single().recipe_satisfied(Recipe(House::Door))
-                {
-                    let texture = asset_server.load("door.png");
-                    commands.spawn((
-                        SpriteBundle {
-                            transform: *cursor.single().1,
-                            texture,
-                            ..default()
-                        },
-                        YSort(0.0),
-                    ));
-
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Wood)
-                        .and_modify(|(_, count)| *count -= 1);
-
-                    commands.entity(cursor.single().0).remove::<OnCursor>();
-                }
+            let cursor_pos = cursor.single().1.translation.truncate();
+            let house_part = cursor2.single().0;
+            let mut grid = grid_query.single_mut();
+            // Keep the part on the cursor until it's dropped on free tiles
+            if let Some(misfit) = part_misfit(&grid, &rapier_context, cursor_pos, house_part) {
+                notices.send(Notice(misfit.message().to_string()));
+                return;
+            }
+            commands
+                .entity(cursor.single().0)
+                .remove::<Sprite>()
+                .remove::<OnCursor>();
+
+            let id = spawn_blueprint(
+                Blueprint::new(house_part),
+                cursor.single().1.translation,
+                &mut commands,
+                &asset_server,
+            );
+            let blueprint = WorldObject::Blueprint(house_part);
+            if place_spawned(id, cursor_pos, blueprint, &mut commands, &mut grid) {
+                queue.tasks.push_back(Task::new(
+                    TaskType::BuildPart(house_part),
+                    grid.world_to_grid(cursor_pos),
+                ));
             }
         }
     }
@@ -938,7 +983,9 @@ fn drop_house_parts(
 fn pickup_object(
     rapier_context: Res<RapierContext>,
     object_query: Query<(Entity, &Transform, &InventoryObject)>,
+    player_query: Query<(), With<Player>>,
     mut inv_query: Query<&mut Inventory>,
+    mut grid_query: Query<&mut Grid>,
     mut commands: Commands,
 ) {
     for (entity, transform, object) in object_query.iter() {
@@ -948,7 +995,9 @@ fn pickup_object(
         let shape_vel = Vec2::new(0.1, 0.1);
         let max_toi = 0.0;
         let stop_at_penetration = true;
-        let filter = QueryFilter::default();
+        // Only the player walks off with items, trees and walls standing on them don't
+        let is_player = |collider| player_query.contains(collider);
+        let filter = QueryFilter::default().predicate(&is_player);
 
         if let Some((_entity, _hit)) = rapier_context.cast_shape(
             shape_pos,
@@ -965,6 +1014,9 @@ fn pickup_object(
                 .entry(*object)
                 .and_modify(|(_, count)| *count += 1);
             commands.entity(entity).despawn_recursive();
+            grid_query
+                .single_mut()
+                .remove_object(transform.translation.truncate(), Layer::Overlay);
         }
     }
 }
@@ -972,125 +1024,323 @@ fn pickup_object(
 fn grow_plants(
     mut sprite_query: Query<(&mut Handle<Image>, &GrowStartTime, &WorldObject)>,
     asset_server: Res<AssetServer>,
-    time: Res<Time>,
+    clock: Res<GameClock>,
+    crops: Crops,
 ) {
     for (mut texture, grow_start_time, object) in sprite_query.iter_mut() {
-        let time = time.elapsed().as_secs() - grow_start_time.0.as_secs();
-        if time > 10 && time <= 20 {
-            *texture = asset_server.load("sprout.png");
-        } else if time > 20 && time <= 30 {
-            if *object == WorldObject::FlowerbedWithBeans {
-                *texture = asset_server.load("beans_level2.png");
-            } else if *object == WorldObject::FlowerbedWithPotatoSeeds {
-                *texture = asset_server.load("potatoes_level2.png");
-            }
-        } else if time > 30 {
-            if *object == WorldObject::FlowerbedWithBeans {
-                *texture = asset_server.load("beans_level3.png");
-            } else if *object == WorldObject::FlowerbedWithPotatoSeeds {
-                *texture = asset_server.load("potatoes_level3.png");
+        let WorldObject::Crop(seed) = object else {
+            continue;
+        };
+        let stage = crops
+            .get(*seed)
+            .and_then(|crop| crop.stage(grow_start_time.grown_for(&clock)));
+        if let Some(stage) = stage {
+            *texture = asset_server.load(&stage.texture);
+        }
+    }
+}
+
+// Inventory the harvest goes into and the crops on the map
+#[derive(SystemParam)]
+struct Garden<'w, 's> {
+    inv_query: Query<'w, 's, &'static mut Inventory>,
+    grid_query: Query<'w, 's, &'static mut Grid>,
+    growth: CropGrowth<'w, 's>,
+}
+
+fn harvest_plants(
+    input: Res<Input<KeyCode>>,
+    garden: Garden,
+    mut hands: PlayerHands,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    let Garden {
+        mut inv_query,
+        mut grid_query,
+        growth: CropGrowth {
+            crops,
+            clock,
+            grow_query,
+        },
+    } = garden;
+    if input.just_pressed(KeyCode::Space) && inv_query.single().items[&InventoryObject::Hoe].0 {
+        let mut grid = grid_query.single_mut();
+        let player_vec2 = hands.pos();
+
+        let Some(crop) = grid.get_object(player_vec2, Layer::Object) else {
+            return;
+        };
+        // Withered crops are only cleared away
+        if crop.object == WorldObject::DeadCrop {
+            if hands.too_tired() {
+                return;
             }
+            clear_crop(crop, &mut commands, &asset_server, &mut grid);
+            hands.work(InventoryObject::Hoe);
+            return;
+        }
+        let WorldObject::Crop(seed) = crop.object else {
+            return;
+        };
+        if is_grown(crop, &crops, &grow_query, &clock) && !hands.too_tired() {
+            hands.work(InventoryObject::Hoe);
+            harvest_crop(
+                crop,
+                crops.get(seed).unwrap(),
+                &mut inv_query.single_mut(),
+                &mut commands,
+                &asset_server,
+                &mut grid,
+            );
+        }
+    }
+}
+
+fn is_grown(
+    crop: Placement,
+    crops: &Crops,
+    grow_query: &Query<&GrowStartTime>,
+    clock: &GameClock,
+) -> bool {
+    let WorldObject::Crop(seed) = crop.object else {
+        return false;
+    };
+    let Ok(grow_start_time) = grow_query.get(crop.entity) else {
+        return false;
+    };
+    crops
+        .get(seed)
+        .is_some_and(|crop_def| crop_def.is_grown(grow_start_time.grown_for(clock)))
+}
+
+// Picks a grown crop, the flowerbed below is left empty for new seeds
+fn harvest_crop(
+    crop: Placement,
+    crop_def: &CropDef,
+    inventory: &mut Inventory,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    grid: &mut Grid,
+) {
+    inventory
+        .items
+        .entry(crop_def.produce)
+        .and_modify(|(_, count)| *count += crop_def.harvest_yield);
+    if rand::thread_rng().gen_bool(crop_def.seed_chance) {
+        inventory
+            .items
+            .entry(crop_def.seed)
+            .and_modify(|(_, count)| *count += 1);
+    }
+
+    clear_crop(crop, commands, asset_server, grid);
+}
+
+// Leaves the flowerbed below the crop empty
+fn clear_crop(
+    crop: Placement,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    grid: &mut Grid,
+) {
+    grid.remove_object(grid.grid_to_world(crop.grid_pos), Layer::Object);
+    let texture: Handle<Image> = asset_server.load("flowerbed.png");
+    commands
+        .entity(crop.entity)
+        .insert((WorldObject::Flowerbed, texture))
+        .remove::<GrowStartTime>();
+}
+
+// Crops out of season die, they stay in the flowerbed until they're cleared
+fn wither_crops(
+    crop_query: Query<(Entity, &Transform, &WorldObject), With<GrowStartTime>>,
+    mut grid_query: Query<&mut Grid>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    clock: Res<GameClock>,
+    crops: Crops,
+) {
+    let season = clock.season();
+    for (entity, transform, object) in crop_query.iter() {
+        let WorldObject::Crop(seed) = object else {
+            continue;
+        };
+        if crops.get(*seed).is_some_and(|crop| !crop.grows_in(season)) {
+            wither_crop(
+                entity,
+                transform.translation.truncate(),
+                &mut commands,
+                &asset_server,
+                &mut grid_query.single_mut(),
+            );
         }
     }
 }
 
+fn wither_crop(
+    flowerbed: Entity,
+    pos: Vec2,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    grid: &mut Grid,
+) {
+    let texture: Handle<Image> = asset_server.load("dead_crop.png");
+    grid.remove_object(pos, Layer::Object);
+    if !grid.place_object(flowerbed, pos, WorldObject::DeadCrop) {
+        return;
+    }
+    commands
+        .entity(flowerbed)
+        .insert((WorldObject::DeadCrop, texture))
+        .remove::<GrowStartTime>();
+}
+
 fn spread_seed(
     input: Res<Input<KeyCode>>,
     mut inv_query: Query<&mut Inventory>,
     mut grid_query: Query<&mut Grid>,
-    player_query: Query<&Transform, With<Player>>,
+    mut hands: PlayerHands,
     mut commands: Commands,
-    mut sprite_query: Query<(Entity, &mut Handle<Image>), With<WorldObject>>,
     asset_server: Res<AssetServer>,
-    time: Res<Time>,
+    clock: Res<GameClock>,
+    crops: Crops,
 ) {
-    if input.just_pressed(KeyCode::Space)
-        && inv_query.single().items[&InventoryObject::Beans].0
-        && inv_query.single().items[&InventoryObject::Beans].1 >= 1
+    if !input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    let Some(seed) = inv_query.single().active_object() else {
+        return;
+    };
+    if !crops
+        .get(seed)
+        .is_some_and(|crop| crop.grows_in(clock.season()))
+        || inv_query.single().items[&seed].1 < 1
     {
-        let mut grid = grid_query.single_mut();
-        let player_vec2 = player_query.single().translation.truncate();
-
-        let obj = grid.get_object(player_vec2);
-        match obj {
-            Some(Placement {
-                entity,
-                grid_pos: _,
-                object,
-            }) => {
-                // Remove flowerbed from grid
-                if object == WorldObject::Flowerbed {
-                    grid.remove_object(player_vec2);
-                }
+        return;
+    }
 
-                for (entity2, mut texture) in sprite_query.iter_mut() {
-                    if entity == entity2 {
-                        *texture = asset_server.load("flowerbed_with_seeds.png");
-                        let now = time.elapsed();
-
-                        grid.place_object(entity, player_vec2, WorldObject::FlowerbedWithBeans);
-                        commands
-                            .entity(entity)
-                            .insert((WorldObject::FlowerbedWithBeans, GrowStartTime(now)));
-
-                        inv_query
-                            .single_mut()
-                            .items
-                            .entry(InventoryObject::Beans)
-                            .and_modify(|(_, count)| *count -= 1);
-                    }
-                }
-            }
-            None => {}
-        }
+    let mut grid = grid_query.single_mut();
+    let player_vec2 = hands.pos();
+    let Some(flowerbed) = grid.get_object(player_vec2, Layer::Ground) else {
+        return;
+    };
+    // Crop grows on top of the flowerbed, in the object layer
+    if flowerbed.object != WorldObject::Flowerbed
+        || !grid.is_layer_free(player_vec2, Layer::Object)
+        || hands.too_tired()
+    {
+        return;
     }
 
-    if input.just_pressed(KeyCode::Space)
-        && inv_query.single().items[&InventoryObject::PotatoSeeds].0
-        && inv_query.single().items[&InventoryObject::PotatoSeeds].1 >= 1
+    plant_seed(
+        seed,
+        flowerbed.entity,
+        player_vec2,
+        &mut commands,
+        &asset_server,
+        &mut grid,
+        clock.elapsed_minutes(),
+    );
+    inv_query
+        .single_mut()
+        .items
+        .entry(seed)
+        .and_modify(|(_, count)| *count -= 1);
+    hands.work(InventoryObject::Hoe);
+}
+
+fn water_plants(
+    input: Res<Input<KeyCode>>,
+    inv_query: Query<&Inventory>,
+    grid_query: Query<&Grid>,
+    mut hands: PlayerHands,
+    mut moisture_query: Query<&mut Moisture>,
+) {
+    if !input.just_pressed(KeyCode::Space)
+        || inv_query.single().active_object() != Some(InventoryObject::WateringCan)
     {
-        let mut grid = grid_query.single_mut();
-        let player_vec2 = player_query.single().translation.truncate();
+        return;
+    }
+    let player_vec2 = hands.pos();
+    let Some(flowerbed) = grid_query.single().get_object(player_vec2, Layer::Ground) else {
+        return;
+    };
+    let Ok(mut moisture) = moisture_query.get_mut(flowerbed.entity) else {
+        return;
+    };
+    if hands.too_tired() {
+        return;
+    }
+    moisture.0 = 1.0;
+    hands.work(InventoryObject::WateringCan);
+}
 
-        let obj = grid.get_object(player_vec2);
-        match obj {
-            Some(Placement {
-                entity,
-                grid_pos: _,
-                object,
-            }) => {
-                // Remove flowerbed from grid
-                if object == WorldObject::Flowerbed {
-                    grid.remove_object(player_vec2);
-                }
+// Soil dries out as the clock runs, crops in dry soil stop growing
+fn dry_out_soil(
+    mut soil_query: Query<(&mut Moisture, Option<&mut GrowStartTime>)>,
+    clock: Res<GameClock>,
+) {
+    let minutes = clock.delta_minutes();
+    for (mut moisture, grow_start_time) in soil_query.iter_mut() {
+        if moisture.is_dry() {
+            if let Some(mut grow_start_time) = grow_start_time {
+                grow_start_time.0 += minutes;
+            }
+            continue;
+        }
+        moisture.0 = (moisture.0 - minutes as f32 / DRYING_MINUTES).max(0.0);
+    }
+}
 
-                for (entity2, mut texture) in sprite_query.iter_mut() {
-                    if entity == entity2 {
-                        *texture = asset_server.load("flowerbed_with_seeds.png");
-                        let now = time.elapsed();
-
-                        grid.place_object(
-                            entity,
-                            player_vec2,
-                            WorldObject::FlowerbedWithPotatoSeeds,
-                        );
-                        commands
-                            .entity(entity)
-                            .insert((WorldObject::FlowerbedWithPotatoSeeds, GrowStartTime(now)));
-
-                        inv_query
-                            .single_mut()
-                            .items
-                            .entry(InventoryObject::PotatoSeeds)
-                            .and_modify(|(_, count)| *count -= 1);
-                    }
+// Empty flowerbeds have their own dry texture, crops are tinted instead
+fn show_soil_moisture(
+    mut soil_query: Query<(&Moisture, &WorldObject, &mut Handle<Image>, &mut Sprite)>,
+    asset_server: Res<AssetServer>,
+) {
+    for (moisture, object, mut texture, mut sprite) in soil_query.iter_mut() {
+        let color = match object {
+            WorldObject::Flowerbed => {
+                let path = if moisture.is_dry() {
+                    "flowerbed_dry.png"
+                } else {
+                    "flowerbed.png"
+                };
+                let handle = asset_server.load(path);
+                if *texture != handle {
+                    *texture = handle;
                 }
+                Color::WHITE
             }
-            None => {}
+            _ if moisture.is_dry() => DRY_SOIL_TINT,
+            _ => Color::WHITE,
+        };
+        if sprite.color != color {
+            sprite.color = color;
         }
     }
 }
 
+// Turns the flowerbed into a crop starting to grow from the seed
+fn plant_seed(
+    seed: InventoryObject,
+    flowerbed: Entity,
+    pos: Vec2,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    grid: &mut Grid,
+    now: f64,
+) {
+    let crop = WorldObject::Crop(seed);
+    let texture: Handle<Image> = asset_server.load("flowerbed_with_seeds.png");
+    if !grid.place_object(flowerbed, pos, crop) {
+        return;
+    }
+    commands
+        .entity(flowerbed)
+        .insert((crop, GrowStartTime(now), texture));
+}
+
 fn spawn_flowerbed(pos: Vec3, commands: &mut Commands, asset_server: &Res<AssetServer>) -> Entity {
     let texture = asset_server.load("flowerbed.png");
     commands
@@ -1108,121 +1358,229 @@ fn spawn_flowerbed(pos: Vec3, commands: &mut Commands, asset_server: &Res<AssetS
                 ..default()
             },
             WorldObject::Flowerbed,
+            Moisture(0.0),
             YSort(-250.0),
         ))
         .id()
 }
 
+fn spawn_tree(pos: Vec3, commands: &mut Commands, asset_server: &Res<AssetServer>) -> Entity {
+    let texture = asset_server.load("tree.png");
+    commands
+        .spawn((
+            SpriteBundle {
+                texture,
+                transform: Transform::from_translation(pos),
+                ..default()
+            },
+            Collider::convex_hull(&[
+                Vect::new(-20.0, -40.0),
+                Vect::new(20.0, -40.0),
+                Vect::new(-20.0, 0.0),
+                Vect::new(20.0, 0.0),
+            ])
+            .unwrap(),
+            WorldObject::Tree,
+            Damage(3),
+            YSort(0.0),
+        ))
+        .id()
+}
+
+// Sapling with no collider yet, it counts its growth from planted_at
+fn spawn_sapling(
+    pos: Vec3,
+    planted_at: f64,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> Entity {
+    let texture = asset_server.load("sapling.png");
+    commands
+        .spawn((
+            SpriteBundle {
+                texture,
+                transform: Transform::from_translation(pos),
+                ..default()
+            },
+            WorldObject::Sapling,
+            GrowStartTime(planted_at),
+            Damage(1),
+            YSort(0.0),
+        ))
+        .id()
+}
+
+fn spawn_rock(pos: Vec3, commands: &mut Commands, asset_server: &Res<AssetServer>) -> Entity {
+    let texture = asset_server.load("rock.png");
+    commands
+        .spawn((
+            SpriteBundle {
+                texture,
+                transform: Transform::from_translation(pos),
+                ..default()
+            },
+            Collider::convex_hull(&[
+                Vect::new(-20.0, -20.0),
+                Vect::new(20.0, -20.0),
+                Vect::new(-20.0, 0.0),
+                Vect::new(20.0, 0.0),
+            ])
+            .unwrap(),
+            WorldObject::Rock,
+            Damage(2),
+            YSort(0.0),
+        ))
+        .id()
+}
+
+fn spawn_grass_tile(pos: Vec3, commands: &mut Commands, asset_server: &Res<AssetServer>) -> Entity {
+    let texture = asset_server.load("grass.png");
+    commands
+        .spawn((
+            SpriteBundle {
+                texture,
+                transform: Transform::from_translation(pos),
+                ..default()
+            },
+            WorldObject::Grass,
+            YSort(0.0),
+        ))
+        .id()
+}
+
+fn spawn_item(
+    object: InventoryObject,
+    pos: Vec3,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> Entity {
+    let texture = asset_server.load(object.texture());
+    commands
+        .spawn((
+            SpriteBundle {
+                texture,
+                transform: Transform::from_translation(pos),
+                ..default()
+            },
+            object,
+            WorldObject::Item(object),
+        ))
+        .id()
+}
+
+// Item lying on the closest free tile, none when there's no room anywhere near
+fn drop_item(
+    object: InventoryObject,
+    pos: Vec2,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    grid: &mut Grid,
+) -> Option<Entity> {
+    let Some(tile) = grid.item_spot(pos) else {
+        warn!("No room to drop {:?}", object);
+        return None;
+    };
+    let id = spawn_item(object, tile.extend(0.0), commands, asset_server);
+    place_spawned(id, tile, WorldObject::Item(object), commands, grid).then_some(id)
+}
+
+// Puts a freshly spawned object on the map, it's despawned again if its tiles are taken
+fn place_spawned(
+    entity: Entity,
+    pos: Vec2,
+    object: WorldObject,
+    commands: &mut Commands,
+    grid: &mut Grid,
+) -> bool {
+    let placed = grid.place_object(entity, pos, object);
+    if !placed {
+        commands.entity(entity).despawn_recursive();
+    }
+    placed
+}
+
+fn spawn_house_part(
+    house_part: House,
+    pos: Vec3,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+) -> Entity {
+    let texture = asset_server.load(house_part.texture());
+    let mut entity = commands.spawn((
+        SpriteBundle {
+            texture,
+            transform: Transform::from_translation(pos),
+            ..default()
+        },
+        WorldObject::House(house_part),
+        YSort(house_part.y_sort()),
+    ));
+    // Door is walkable
+    if house_part != House::Door {
+        entity.insert(house_part.collider());
+    }
+    if house_part == House::Wall {
+        entity.insert(WallLinks::default());
+    }
+    entity.id()
+}
+
 fn dig_flowerbed(
     input: Res<Input<KeyCode>>,
     inv_query: Query<&Inventory>,
     mut grid_query: Query<&mut Grid>,
-    player_query: Query<&Transform, With<Player>>,
-    mut anim_query: Query<
-        (
-            &mut AnimationIndices,
-            &mut TextureAtlasSprite,
-            &mut Movement,
-        ),
-        With<Player>,
-    >,
+    mut hands: PlayerHands,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
 ) {
     if input.just_pressed(KeyCode::Space) && inv_query.single().items[&InventoryObject::Hoe].0 {
         let mut grid = grid_query.single_mut();
-        let player_vec2 = player_query.single().translation.truncate();
+        let player_vec2 = hands.pos();
 
         // Remove grass
-        let obj = grid.get_object(player_vec2);
+        let obj = grid.get_object(player_vec2, Layer::Ground);
         match obj {
             Some(Placement {
                 entity,
                 grid_pos: _,
+                origin: _,
                 object,
             }) => {
                 if object == WorldObject::Grass {
+                    if hands.too_tired() {
+                        return;
+                    }
                     commands.entity(entity).despawn();
-                    grid.remove_object(player_vec2);
+                    grid.remove_object(player_vec2, Layer::Ground);
+                    hands.work(InventoryObject::Hoe);
                     return;
                 }
             }
             None => {}
         }
 
-        let pos = grid.world_to_grid(player_vec2);
-        let (mut anim_indices, mut sprite, mut movement) = anim_query.single_mut();
-        match *movement {
-            Movement::Up => {
-                anim_indices.first = 19;
-                anim_indices.last = 19;
-                *sprite = TextureAtlasSprite::new(19);
-
-                let pos2 = grid.grid_to_world(pos);
-                if grid.is_free(pos2) {
-                    let id = spawn_flowerbed(pos2.extend(0.0), &mut commands, &asset_server);
-                    grid.place_object(id, pos2, WorldObject::Flowerbed);
-                }
-            }
-            Movement::Down => {
-                anim_indices.first = 24;
-                anim_indices.last = 24;
-                *sprite = TextureAtlasSprite::new(24);
-
-                let pos2 = grid.grid_to_world(pos);
-                if grid.is_free(pos2) {
-                    let id = spawn_flowerbed(pos2.extend(0.0), &mut commands, &asset_server);
-                    grid.place_object(id, pos2, WorldObject::Flowerbed);
-                }
-            }
-            Movement::Left => {
-                anim_indices.first = 25;
-                anim_indices.last = 25;
-                *sprite = TextureAtlasSprite::new(25);
-
-                let pos2 = grid.grid_to_world(pos);
-                if grid.is_free(pos2) {
-                    let id = spawn_flowerbed(pos2.extend(0.0), &mut commands, &asset_server);
-                    grid.place_object(id, pos2, WorldObject::Flowerbed);
-                }
-            }
-            Movement::Right => {
-                anim_indices.first = 26;
-                anim_indices.last = 26;
-                *sprite = TextureAtlasSprite::new(26);
-
-                let pos2 = grid.grid_to_world(pos);
-                if grid.is_free(pos2) {
-                    let id = spawn_flowerbed(pos2.extend(0.0), &mut commands, &asset_server);
-                    grid.place_object(id, pos2, WorldObject::Flowerbed);
-                }
-            }
-            Movement::Working => {}
-            Movement::None => {
-                anim_indices.first = 24;
-                anim_indices.last = 24;
-                *sprite = TextureAtlasSprite::new(24);
-            }
+        // Standing still the hoe only strikes a pose, it digs in facing somewhere
+        let pos = grid.grid_to_world(grid.world_to_grid(player_vec2));
+        if !hands.facing() || !grid.is_free(pos) {
+            hands.pose(InventoryObject::Hoe);
+            return;
         }
-        *movement = Movement::Working;
+        if hands.too_tired() {
+            return;
+        }
+        let id = spawn_flowerbed(pos.extend(0.0), &mut commands, &asset_server);
+        place_spawned(id, pos, WorldObject::Flowerbed, &mut commands, &mut grid);
+        hands.work(InventoryObject::Hoe);
     }
 }
 
 fn break_object(
-    player_query: Query<&Transform, With<Player>>,
+    mut hands: PlayerHands,
     mut object_query: Query<(Entity, &Transform, &mut Damage, &WorldObject)>,
     input: Res<Input<KeyCode>>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     inv_query: Query<&Inventory>,
-    mut anim_query: Query<
-        (
-            &mut AnimationIndices,
-            &mut TextureAtlasSprite,
-            &mut Movement,
-        ),
-        With<Player>,
-    >,
     mut grid_query: Query<&mut Grid>,
 ) {
     if input.just_pressed(KeyCode::Space)
@@ -1230,16 +1588,16 @@ fn break_object(
             || inv_query.single().items[&InventoryObject::Pickaxe].0)
     {
         // Find nearest object
-        let player = player_query.single();
+        let player = hands.pos();
         let mut nearest_entity: Option<(Entity, f32)> = None;
         for (entity, transform, _, _) in object_query.iter() {
             match nearest_entity {
                 None => {
-                    let distance = transform.translation.distance(player.translation);
+                    let distance = transform.translation.truncate().distance(player);
                     nearest_entity = Some((entity, distance));
                 }
                 Some((_, distance2)) => {
-                    let distance = transform.translation.distance(player.translation);
+                    let distance = transform.translation.truncate().distance(player);
                     if distance < distance2 {
                         nearest_entity = Some((entity, distance));
                     }
@@ -1251,111 +1609,24 @@ fn break_object(
         for (entity, transform, mut damage, object) in object_query.iter_mut() {
             match nearest_entity {
                 Some((entity2, distance2)) => {
-                    if entity == entity2 {
-                        if distance2 < 80.0 {
-                            let (mut anim_indices, mut sprite, mut movement) =
-                                anim_query.single_mut();
-                            if object == &WorldObject::Tree
-                                && inv_query.single().items[&InventoryObject::Axe].0
-                            {
-                                damage.0 -= 1;
-                                match *movement {
-                                    Movement::Up => {
-                                        anim_indices.first = 19;
-                                        anim_indices.last = 19;
-                                        *sprite = TextureAtlasSprite::new(19);
-                                    }
-                                    Movement::Down => {
-                                        anim_indices.first = 16;
-                                        anim_indices.last = 16;
-                                        *sprite = TextureAtlasSprite::new(16);
-                                    }
-                                    Movement::Left => {
-                                        anim_indices.first = 17;
-                                        anim_indices.last = 17;
-                                        *sprite = TextureAtlasSprite::new(17);
-                                    }
-                                    Movement::Right => {
-                                        anim_indices.first = 18;
-                                        anim_indices.last = 18;
-                                        *sprite = TextureAtlasSprite::new(18);
-                                    }
-                                    Movement::Working => {}
-                                    Movement::None => {
-                                        anim_indices.first = 16;
-                                        anim_indices.last = 16;
-                                        *sprite = TextureAtlasSprite::new(16);
-                                    }
-                                }
-                                *movement = Movement::Working;
-                            }
-                            if object == &WorldObject::Rock
-                                && inv_query.single().items[&InventoryObject::Pickaxe].0
-                            {
-                                damage.0 -= 1;
-                                match *movement {
-                                    Movement::Up => {
-                                        anim_indices.first = 19;
-                                        anim_indices.last = 19;
-                                        *sprite = TextureAtlasSprite::new(19);
-                                    }
-                                    Movement::Down => {
-                                        anim_indices.first = 20;
-                                        anim_indices.last = 20;
-                                        *sprite = TextureAtlasSprite::new(20);
-                                    }
-                                    Movement::Left => {
-                                        anim_indices.first = 21;
-                                        anim_indices.last = 21;
-                                        *sprite = TextureAtlasSprite::new(21);
-                                    }
-                                    Movement::Right => {
-                                        anim_indices.first = 22;
-                                        anim_indices.last = 22;
-                                        *sprite = TextureAtlasSprite::new(22);
-                                    }
-                                    Movement::Working => {}
-                                    Movement::None => {
-                                        anim_indices.first = 20;
-                                        anim_indices.last = 20;
-                                        *sprite = TextureAtlasSprite::new(20);
-                                    }
-                                }
-                                *movement = Movement::Working;
-                            }
-                        }
-
-                        if damage.0 == 0 {
-                            commands.entity(entity).despawn_recursive();
-                            grid_query
-                                .single_mut()
-                                .remove_object(transform.translation.truncate());
-
-                            // Spawn wood
-                            if *object == WorldObject::Tree {
-                                let texture = asset_server.load("wood.png");
-                                commands.spawn((
-                                    SpriteBundle {
-                                        texture,
-                                        transform: *transform,
-                                        ..default()
-                                    },
-                                    InventoryObject::Wood,
-                                ));
-                            }
-
-                            // Spawn rocks
-                            if *object == WorldObject::Rock {
-                                let texture = asset_server.load("rocks.png");
-                                commands.spawn((
-                                    SpriteBundle {
-                                        texture,
-                                        transform: *transform,
-                                        ..default()
-                                    },
-                                    InventoryObject::Rocks,
-                                ));
+                    if entity == entity2 && distance2 < 80.0 {
+                        let tool = object
+                            .breaking_tool()
+                            .filter(|tool| inv_query.single().items[tool].0);
+                        if let Some(tool) = tool {
+                            if hands.too_tired() {
+                                return;
                             }
+                            hands.work(tool);
+                            hit_object(
+                                entity,
+                                transform,
+                                &mut damage,
+                                object,
+                                &mut commands,
+                                &asset_server,
+                                &mut grid_query.single_mut(),
+                            );
                         }
                     }
                 }
@@ -1365,140 +1636,149 @@ fn break_object(
     }
 }
 
-fn spawn_trees(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut grid_query: Query<&mut Grid>,
-) {
-    let mut rng = rand::thread_rng();
-    let mut trees_num = 0;
-    loop {
-        let x = rng.gen_range(-700.0..700.0);
-        let y = rng.gen_range(-700.0..700.0);
-        let vec = Vec2::new(x, y);
-        // Don't spawn near player
-        if x < 100.0 && x > -100.0 || y < 100.0 && y > -100.0 {
-            continue;
-        }
+// Takes one hit off the object, breaking it down into items once it's out of damage
+fn hit_object(
+    entity: Entity,
+    transform: &Transform,
+    damage: &mut Damage,
+    object: &WorldObject,
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    grid: &mut Grid,
+) -> bool {
+    damage.0 -= 1;
+    if damage.0 > 0 {
+        return false;
+    }
 
-        let mut grid = grid_query.single_mut();
-        if grid.is_free(vec) {
-            let vec2 = grid.grid_to_world(grid.world_to_grid(vec));
-            let texture = asset_server.load("tree.png");
-            let id = commands
-                .spawn((
-                    SpriteBundle {
-                        texture,
-                        transform: Transform::from_xyz(vec2.x, vec2.y, 0.0),
-                        ..default()
-                    },
-                    Collider::convex_hull(&[
-                        Vect::new(-20.0, -40.0),
-                        Vect::new(20.0, -40.0),
-                        Vect::new(-20.0, 0.0),
-                        Vect::new(20.0, 0.0),
-                    ])
-                    .unwrap(),
-                    WorldObject::Tree,
-                    Damage(3),
-                    YSort(0.0),
-                ))
-                .id();
-            grid.place_object(id, vec, WorldObject::Tree);
-            trees_num += 1
-        } else {
-            continue;
-        }
-        if trees_num == 20 {
-            break;
-        }
+    commands.entity(entity).despawn_recursive();
+    grid.remove_object(transform.translation.truncate(), object.layer());
+
+    // Spawn wood or rocks
+    let drop = match object {
+        WorldObject::Tree => Some(InventoryObject::Wood),
+        WorldObject::Rock => Some(InventoryObject::Rocks),
+        WorldObject::Sapling => Some(InventoryObject::Sapling),
+        _ => None,
+    };
+    if *object == WorldObject::Tree {
+        let pos = transform.translation.truncate();
+        commands.add(move |world: &mut World| {
+            world.send_event(TreeFelled(pos));
+        });
     }
+    if let Some(item) = drop {
+        drop_item(
+            item,
+            transform.translation.truncate(),
+            commands,
+            asset_server,
+            grid,
+        );
+    }
+    true
 }
 
-fn spawn_rocks(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut grid_query: Query<&mut Grid>,
-) {
-    let mut rng = rand::thread_rng();
-    let mut rocks_num = 0;
-    loop {
-        let x = rng.gen_range(-700.0..700.0);
-        let y = rng.gen_range(-700.0..700.0);
-        let vec = Vec2::new(x, y);
-        // Don't spawn near player
-        if x < 100.0 && x > -100.0 || y < 100.0 && y > -100.0 {
-            continue;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessGame;
+    use crate::hud_ui::NoticeText;
+
+    #[test]
+    fn axe_breaks_tree_into_wood() {
+        let mut game = HeadlessGame::new();
+        game.place(WorldObject::Tree, (0, 1));
+        game.press(KeyCode::Key1);
+        assert_eq!(game.inventory().active_object(), Some(InventoryObject::Axe));
+
+        for _ in 0..3 {
+            game.press(KeyCode::Space);
         }
+        assert_eq!(game.object_at((0, 1), Layer::Object), None);
+        assert_eq!(
+            game.object_at((0, 1), Layer::Overlay),
+            Some(WorldObject::Item(InventoryObject::Wood))
+        );
+
+        // Walk onto the dropped wood to pick it up
+        game.hold(KeyCode::W);
+        let picked_up = game.step_until(120, |game| game.count(InventoryObject::Wood) == 1);
+        game.release(KeyCode::W);
+        assert!(picked_up);
+        assert_eq!(game.object_at((0, 1), Layer::Overlay), None);
+    }
 
-        let mut grid = grid_query.single_mut();
-        if grid.is_free(vec) {
-            let vec2 = grid.grid_to_world(grid.world_to_grid(vec));
-            let texture = asset_server.load("rock.png");
-            let id = commands
-                .spawn((
-                    SpriteBundle {
-                        texture,
-                        transform: Transform::from_xyz(vec2.x, vec2.y, 0.0),
-                        ..default()
-                    },
-                    Collider::convex_hull(&[
-                        Vect::new(-20.0, -20.0),
-                        Vect::new(20.0, -20.0),
-                        Vect::new(-20.0, 0.0),
-                        Vect::new(20.0, 0.0),
-                    ])
-                    .unwrap(),
-                    WorldObject::Rock,
-                    Damage(2),
-                    YSort(0.0),
-                ))
-                .id();
-            grid.place_object(id, vec, WorldObject::Rock);
-            rocks_num += 1
-        } else {
-            continue;
-        }
-        if rocks_num == 20 {
-            break;
+    #[test]
+    fn drop_rolls_off_a_taken_tile() {
+        let mut game = HeadlessGame::new();
+        game.place(WorldObject::Tree, (0, 1));
+        game.place(WorldObject::Item(InventoryObject::Rocks), (0, 1));
+        game.press(KeyCode::Key1);
+        for _ in 0..3 {
+            game.press(KeyCode::Space);
         }
+        game.step(1);
+
+        // First free tile next to the stump, out of the player's reach
+        assert_eq!(
+            game.object_at((-1, 1), Layer::Overlay),
+            Some(WorldObject::Item(InventoryObject::Wood))
+        );
+        assert_eq!(game.count(InventoryObject::Wood), 0);
+        assert_eq!(
+            game.object_at((0, 1), Layer::Overlay),
+            Some(WorldObject::Item(InventoryObject::Rocks))
+        );
     }
-}
 
-fn spawn_grass(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut grid_query: Query<&mut Grid>,
-) {
-    let mut rng = rand::thread_rng();
-    let mut grass_num = 0;
-    loop {
-        let x = rng.gen_range(-700.0..700.0);
-        let y = rng.gen_range(-700.0..700.0);
-        let vec = Vec2::new(x, y);
+    #[test]
+    fn two_hits_dont_break_tree() {
+        let mut game = HeadlessGame::new();
+        game.place(WorldObject::Tree, (0, 1));
+        game.press(KeyCode::Key1);
+        game.press(KeyCode::Space);
+        game.press(KeyCode::Space);
+        assert_eq!(
+            game.object_at((0, 1), Layer::Object),
+            Some(WorldObject::Tree)
+        );
+    }
 
-        let mut grid = grid_query.single_mut();
-        if grid.is_free(vec) {
-            let vec2 = grid.grid_to_world(grid.world_to_grid(vec));
-            let texture = asset_server.load("grass.png");
-            let id = commands
-                .spawn((
-                    SpriteBundle {
-                        texture,
-                        transform: Transform::from_xyz(vec2.x, vec2.y, 0.0),
-                        ..default()
-                    },
-                    WorldObject::Grass,
-                    YSort(0.0),
-                ))
-                .id();
-            grid.place_object(id, vec, WorldObject::Grass);
-            grass_num += 1
-        } else {
-            continue;
-        }
-        if grass_num == 20 {
-            break;
+    fn notice(game: &mut HeadlessGame) -> String {
+        let mut query = game.app.world.query_filtered::<&Text, With<NoticeText>>();
+        query.single(&game.app.world).sections[0].value.clone()
+    }
+
+    #[test]
+    fn blocked_drop_is_rejected() {
+        let mut game = HeadlessGame::new();
+        game.place(WorldObject::Tree, (4, 0));
+        let cursor = game.pick_part(House::Wall3);
+        let ghost_tint =
+            |game: &mut HeadlessGame| game.app.world.get::<Sprite>(cursor).unwrap().color;
+
+        // On the player, then on the tree
+        for (tile, misfit) in [((0, 1), Misfit::InTheWay), ((4, 1), Misfit::Taken)] {
+            game.move_cursor(tile);
+            game.step(1);
+            assert_eq!(ghost_tint(&mut game), INVALID_GHOST_TINT);
+            game.click(MouseButton::Left);
+            assert!(game.app.world.entity(cursor).contains::<OnCursor>());
+            assert_eq!(notice(&mut game), misfit.message());
         }
+        assert_eq!(
+            game.object_at((4, 0), Layer::Object),
+            Some(WorldObject::Tree)
+        );
+
+        game.move_cursor((2, 3));
+        game.step(1);
+        assert_eq!(ghost_tint(&mut game), VALID_GHOST_TINT);
+        game.click(MouseButton::Left);
+        assert_eq!(
+            game.object_at((2, 2), Layer::Object),
+            Some(WorldObject::Blueprint(House::Wall3))
+        );
     }
 }
@@ -1,3 +1,4 @@
+use crate::save::{save_exists, LoadGame};
 use crate::AppState;
 
 use bevy::prelude::*;
@@ -8,11 +9,19 @@ struct Menu;
 #[derive(Component)]
 struct PlayButton;
 
+#[derive(Component)]
+struct ContinueButton;
+
 pub struct MenuUiPlugin;
 impl Plugin for MenuUiPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::Menu), spawn_menu)
-            .add_systems(Update, interact_with_play_button.run_if(in_state(AppState::Menu)))
+            .add_systems(
+                Update,
+                (interact_with_play_button, interact_with_continue_button)
+                    .run_if(in_state(AppState::Menu)),
+            )
+            .add_systems(Update, open_menu.run_if(in_state(AppState::InGame)))
             .add_systems(OnExit(AppState::Menu), despawn_menu);
     }
 }
@@ -28,8 +37,10 @@ fn spawn_menu(mut commands: Commands) {
                 // center button
                 width: Val::Percent(100.),
                 height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
+                row_gap: Val::Px(10.),
                 ..default()
             },
             background_color: Color::BLACK.into(),
@@ -64,6 +75,35 @@ fn spawn_menu(mut commands: Commands) {
                         },
                     ));
                 });
+
+            // Only offer continuing when there is something to load
+            if save_exists() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(150.),
+                                height: Val::Px(65.),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: NORMAL_BUTTON.into(),
+                            ..default()
+                        },
+                        ContinueButton,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            "Continue",
+                            TextStyle {
+                                font_size: 40.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
         });
 }
 
@@ -90,6 +130,37 @@ fn interact_with_play_button(
     }
 }
 
+fn interact_with_continue_button(
+    mut next_state: ResMut<NextState<AppState>>,
+    mut load_events: EventWriter<LoadGame>,
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ContinueButton>),
+    >,
+) {
+    for (interaction, mut color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+                load_events.send(LoadGame);
+                next_state.set(AppState::InGame);
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+            }
+        }
+    }
+}
+
+fn open_menu(mut next_state: ResMut<NextState<AppState>>, input: Res<Input<KeyCode>>) {
+    if input.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::Menu);
+    }
+}
+
 fn despawn_menu(mut commands: Commands, query: Query<Entity, With<Menu>>) {
     commands.entity(query.single()).despawn_recursive();
 }
@@ -1,9 +1,15 @@
 use crate::AppState;
 
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use crate::YSort;
+use crate::energy::Energy;
+use crate::{InventoryObject, YSort};
+
+pub const SPEED: f32 = 150.0;
+// Seconds each animation frame is shown
+pub const FRAME_SECONDS: f32 = 0.3;
 
 #[derive(Component)]
 pub struct Player;
@@ -15,7 +21,11 @@ pub struct AnimationIndices {
 }
 
 #[derive(Component, Deref, DerefMut)]
-struct AnimationTimer(Timer);
+pub struct AnimationTimer(Timer);
+
+// Spritesheet shared by the player and colonists
+#[derive(Resource)]
+pub struct CharacterAtlas(pub Handle<TextureAtlas>);
 
 #[derive(Component, PartialEq)]
 pub enum Movement {
@@ -27,17 +37,63 @@ pub enum Movement {
     None,
 }
 
+// Where the player stands, what it takes to show them working and the energy the work costs
+#[derive(SystemParam)]
+pub struct PlayerHands<'w, 's> {
+    query: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static mut AnimationIndices,
+            &'static mut TextureAtlasSprite,
+            &'static mut Movement,
+            &'static mut Energy,
+        ),
+        With<Player>,
+    >,
+}
+
+impl PlayerHands<'_, '_> {
+    pub fn pos(&self) -> Vec2 {
+        self.query.single().0.translation.truncate()
+    }
+
+    pub fn too_tired(&self) -> bool {
+        self.query.single().4.too_tired()
+    }
+
+    // Walking somewhere, standing still or busy working the player isn't turned to any tile
+    pub fn facing(&self) -> bool {
+        !matches!(*self.query.single().3, Movement::None | Movement::Working)
+    }
+
+    // Swings the tool without getting anything done, that's free
+    pub fn pose(&mut self, tool: InventoryObject) {
+        let (_, mut anim_indices, mut sprite, mut movement, _) = self.query.single_mut();
+        set_work_animation(tool, &mut anim_indices, &mut sprite, &mut movement);
+    }
+
+    // Done once the action has happened, it's what gets charged
+    pub fn work(&mut self, tool: InventoryObject) {
+        self.pose(tool);
+        self.query.single_mut().4.spend();
+    }
+}
+
 pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PostStartup, setup_player).add_systems(
-            Update,
-            (character_movement, animate_sprite).run_if(in_state(AppState::InGame)),
-        );
+        app.add_systems(Startup, load_character_atlas)
+            .add_systems(PostStartup, setup_player)
+            .add_systems(
+                Update,
+                (character_movement, animate_sprite).run_if(in_state(AppState::InGame)),
+            );
     }
 }
 
-fn setup_player(
+fn load_character_atlas(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
@@ -45,18 +101,22 @@ fn setup_player(
     let texture_handle = asset_server.load("spritesheet.png");
     let texture_atlas =
         TextureAtlas::from_grid(texture_handle, Vec2::new(100.0, 100.0), 4, 7, None, None);
-    let texture_atlas_handle = texture_atlases.add(texture_atlas);
+    commands.insert_resource(CharacterAtlas(texture_atlases.add(texture_atlas)));
+}
+
+// Sprite, animation and collider of anyone walking around the world
+pub fn character_bundle(atlas: &CharacterAtlas, pos: Vec3) -> impl Bundle {
     // Use only the subset of sprites in the sheet that make up the run animation
     let animation_indices = AnimationIndices { first: 2, last: 2 };
-    commands.spawn((
-        Player,
+    (
         SpriteSheetBundle {
-            texture_atlas: texture_atlas_handle,
+            texture_atlas: atlas.0.clone(),
             sprite: TextureAtlasSprite::new(animation_indices.first),
+            transform: Transform::from_translation(pos),
             ..default()
         },
         animation_indices,
-        AnimationTimer(Timer::from_seconds(0.3, TimerMode::Repeating)),
+        AnimationTimer(Timer::from_seconds(FRAME_SECONDS, TimerMode::Repeating)),
         KinematicCharacterController::default(),
         RigidBody::KinematicPositionBased,
         Collider::convex_hull(&[
@@ -68,6 +128,14 @@ fn setup_player(
         .unwrap(),
         Movement::None,
         YSort(0.0),
+    )
+}
+
+fn setup_player(mut commands: Commands, atlas: Res<CharacterAtlas>) {
+    commands.spawn((
+        Player,
+        Energy::default(),
+        character_bundle(&atlas, Vec3::ZERO),
     ));
 }
 
@@ -100,6 +168,26 @@ fn character_movement(
 
     let mut controller = controller_query.single_mut();
     let (mut anim_indices, mut sprite, mut movement) = anim_query.single_mut();
+    set_walk_animation(x, y, &mut anim_indices, &mut sprite, &mut movement);
+
+    if x == 0.0 && y == 0.0 {
+        set_idle_animation(&mut anim_indices, &mut sprite, &mut movement);
+    } else {
+        let v2_norm = Vec2::new(x, y).normalize();
+        controller.translation = Some(Vec2::new(
+            v2_norm.x * SPEED * time.delta_seconds(),
+            v2_norm.y * SPEED * time.delta_seconds(),
+        ))
+    }
+}
+
+pub fn set_walk_animation(
+    x: f32,
+    y: f32,
+    anim_indices: &mut AnimationIndices,
+    sprite: &mut TextureAtlasSprite,
+    movement: &mut Movement,
+) {
     if x == 1.0 && y == 0.0 {
         if *movement != Movement::Right {
             anim_indices.first = 8;
@@ -157,46 +245,69 @@ fn character_movement(
         }
         *movement = Movement::Down;
     }
+}
 
-    if x == 0.0 && y == 0.0 {
-        match *movement {
-            Movement::Up => {
-                anim_indices.first = 6;
-                anim_indices.last = 6;
-                *sprite = TextureAtlasSprite::new(6)
-            }
-            Movement::Down => {
-                anim_indices.first = 2;
-                anim_indices.last = 2;
-                *sprite = TextureAtlasSprite::new(2)
-            }
-            Movement::Left => {
-                anim_indices.first = 13;
-                anim_indices.last = 13;
-                *sprite = TextureAtlasSprite::new(13)
-            }
-            Movement::Right => {
-                anim_indices.first = 9;
-                anim_indices.last = 9;
-                *sprite = TextureAtlasSprite::new(9)
-            }
-            Movement::Working => {}
-            Movement::None => {
-                anim_indices.first = 2;
-                anim_indices.last = 2;
-                *sprite = TextureAtlasSprite::new(2)
-            }
+pub fn set_idle_animation(
+    anim_indices: &mut AnimationIndices,
+    sprite: &mut TextureAtlasSprite,
+    movement: &mut Movement,
+) {
+    match *movement {
+        Movement::Up => {
+            anim_indices.first = 6;
+            anim_indices.last = 6;
+            *sprite = TextureAtlasSprite::new(6)
+        }
+        Movement::Down => {
+            anim_indices.first = 2;
+            anim_indices.last = 2;
+            *sprite = TextureAtlasSprite::new(2)
+        }
+        Movement::Left => {
+            anim_indices.first = 13;
+            anim_indices.last = 13;
+            *sprite = TextureAtlasSprite::new(13)
+        }
+        Movement::Right => {
+            anim_indices.first = 9;
+            anim_indices.last = 9;
+            *sprite = TextureAtlasSprite::new(9)
+        }
+        Movement::Working => {}
+        Movement::None => {
+            anim_indices.first = 2;
+            anim_indices.last = 2;
+            *sprite = TextureAtlasSprite::new(2)
         }
-    } else {
-        const SPEED: f32 = 150.0;
-        let v2_norm = Vec2::new(x, y).normalize();
-        controller.translation = Some(Vec2::new(
-            v2_norm.x * SPEED * time.delta_seconds(),
-            v2_norm.y * SPEED * time.delta_seconds(),
-        ))
     }
 }
 
+// Shows the tool being swung in the direction the character is facing
+pub fn set_work_animation(
+    tool: InventoryObject,
+    anim_indices: &mut AnimationIndices,
+    sprite: &mut TextureAtlasSprite,
+    movement: &mut Movement,
+) {
+    let (up, down, left, right) = match tool {
+        InventoryObject::Pickaxe => (19, 20, 21, 22),
+        InventoryObject::Hoe => (19, 24, 25, 26),
+        _ => (19, 16, 17, 18),
+    };
+    let index = match *movement {
+        Movement::Up => up,
+        Movement::Down => down,
+        Movement::Left => left,
+        Movement::Right => right,
+        Movement::Working => return,
+        Movement::None => down,
+    };
+    anim_indices.first = index;
+    anim_indices.last = index;
+    *sprite = TextureAtlasSprite::new(index);
+    *movement = Movement::Working;
+}
+
 fn animate_sprite(
     time: Res<Time>,
     mut query: Query<(
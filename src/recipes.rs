@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::hud_ui::House;
+use crate::ron_loader::RonLoader;
+use crate::{Inventory, InventoryObject, Recipe};
+
+const RECIPES_PATH: &str = "recipes.ron";
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct RecipeDefs {
+    pub recipes: HashMap<House, Vec<(InventoryObject, i32)>>,
+}
+
+// Kept around so the file stays loaded and is reloaded on changes
+#[derive(Resource)]
+struct RecipesHandle(Handle<RecipeDefs>);
+
+pub struct RecipesPlugin;
+impl Plugin for RecipesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<RecipeDefs>()
+            .register_asset_loader(RonLoader::<RecipeDefs>::new(&["recipes.ron"]))
+            .add_systems(Startup, load_recipes)
+            .add_systems(Update, apply_recipes);
+    }
+}
+
+fn load_recipes(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(RecipesHandle(asset_server.load(RECIPES_PATH)));
+}
+
+fn apply_recipes(
+    mut events: EventReader<AssetEvent<RecipeDefs>>,
+    recipe_defs: Res<Assets<RecipeDefs>>,
+    handle: Res<RecipesHandle>,
+    mut inv_query: Query<&mut Inventory>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        if *id != handle.0.id() {
+            continue;
+        }
+        let Some(recipe_defs) = recipe_defs.get(*id) else {
+            continue;
+        };
+        info!("Loaded {} building recipes", recipe_defs.recipes.len());
+        inv_query.single_mut().recipes = recipe_defs
+            .recipes
+            .iter()
+            .map(|(house_part, items)| (Recipe(*house_part), items.clone()))
+            .collect();
+    }
+}
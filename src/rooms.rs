@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::hud_ui::House;
+use crate::{AppState, Grid, Layer, WorldObject};
+
+// Largest area still counted as a room, flooding further means the walls have a gap
+const MAX_ROOM_TILES: usize = 400;
+
+// Room walled in by house parts, with at least one door to get in
+#[derive(Component)]
+pub struct Building {
+    pub interior: HashSet<(i32, i32)>,
+}
+
+// Answers which building a tile is inside of
+#[derive(SystemParam)]
+pub struct Buildings<'w, 's> {
+    query: Query<'w, 's, (Entity, &'static Building)>,
+}
+
+impl Buildings<'_, '_> {
+    pub fn owner(&self, tile: (i32, i32)) -> Option<Entity> {
+        self.query
+            .iter()
+            .find(|(_, building)| building.interior.contains(&tile))
+            .map(|(entity, _)| entity)
+    }
+
+    pub fn is_indoors(&self, tile: (i32, i32)) -> bool {
+        self.owner(tile).is_some()
+    }
+}
+
+pub struct RoomsPlugin;
+impl Plugin for RoomsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, detect_rooms.run_if(in_state(AppState::InGame)));
+    }
+}
+
+// Rebuilds every building whenever something is placed or taken off the map
+fn detect_rooms(
+    changed_query: Query<&WorldObject, Changed<WorldObject>>,
+    mut removed: RemovedComponents<WorldObject>,
+    grid_query: Query<&Grid>,
+    building_query: Query<Entity, With<Building>>,
+    mut commands: Commands,
+) {
+    let house_changed = changed_query
+        .iter()
+        .any(|object| matches!(object, WorldObject::House(_)));
+    if !house_changed && removed.read().count() == 0 {
+        return;
+    }
+    for entity in building_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let grid = grid_query.single();
+    let walls: HashMap<(i32, i32), House> = grid
+        .placements()
+        .filter_map(|placement| match placement.object {
+            WorldObject::House(house_part) if placement.object.layer() == Layer::Object => {
+                Some((placement.grid_pos, house_part))
+            }
+            _ => None,
+        })
+        .collect();
+
+    // Rooms start right inside some wall, every tile is flooded from only once
+    let mut visited = HashSet::new();
+    for (x, y) in walls.keys() {
+        for start in neighbours((*x, *y)) {
+            if walls.contains_key(&start) || visited.contains(&start) {
+                continue;
+            }
+            let (tiles, enclosed) = flood(start, &walls);
+            visited.extend(tiles.iter().copied());
+            if !enclosed {
+                continue;
+            }
+
+            let has_door = tiles
+                .iter()
+                .flat_map(|tile| neighbours(*tile))
+                .any(|tile| walls.get(&tile) == Some(&House::Door));
+            if !has_door {
+                continue;
+            }
+            debug!("Found a building of {} tiles", tiles.len());
+            commands.spawn(Building { interior: tiles });
+        }
+    }
+}
+
+fn neighbours((x, y): (i32, i32)) -> [(i32, i32); 4] {
+    [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+}
+
+// Tiles reachable from the start without crossing a wall, and whether they ran out before the open
+fn flood(start: (i32, i32), walls: &HashMap<(i32, i32), House>) -> (HashSet<(i32, i32)>, bool) {
+    let mut tiles = HashSet::from([start]);
+    let mut frontier = VecDeque::from([start]);
+    while let Some(tile) = frontier.pop_front() {
+        for next in neighbours(tile) {
+            if walls.contains_key(&next) || !tiles.insert(next) {
+                continue;
+            }
+            if tiles.len() > MAX_ROOM_TILES {
+                return (tiles, false);
+            }
+            frontier.push_back(next);
+        }
+    }
+    (tiles, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessGame;
+
+    fn buildings(game: &mut HeadlessGame) -> Vec<&Building> {
+        let mut query = game.app.world.query::<&Building>();
+        query.iter(&game.app.world).collect()
+    }
+
+    #[test]
+    fn walled_room_with_door_is_a_building() {
+        let mut game = HeadlessGame::new();
+        game.build_room(House::Door);
+        let buildings = buildings(&mut game);
+        assert_eq!(buildings.len(), 1);
+        assert_eq!(buildings[0].interior.len(), 20);
+        assert!(buildings[0].interior.contains(&(4, 2)));
+        assert!(!buildings[0].interior.contains(&(2, 3)));
+    }
+
+    #[test]
+    fn room_without_door_isnt_a_building() {
+        let mut game = HeadlessGame::new();
+        game.build_room(House::Wall3);
+        assert!(buildings(&mut game).is_empty());
+    }
+}
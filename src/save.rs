@@ -0,0 +1,402 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::blueprints::{spawn_blueprint, Blueprint};
+use crate::clock::GameClock;
+use crate::colonists::{spawn_colonist, Colonist};
+use crate::energy::Energy;
+use crate::player::{CharacterAtlas, Player};
+use crate::tasks::{Schedule, Task, TaskQueue, Worker};
+use crate::{
+    place_spawned, plant_seed, spawn_flowerbed, spawn_grass_tile, spawn_house_part, spawn_item,
+    spawn_rock, spawn_sapling, spawn_tree, wither_crop, AppState, Grid, GrowStartTime, Inventory,
+    InventoryObject, Layer, Moisture, WorldObject,
+};
+
+#[cfg(not(test))]
+const SAVE_PATH: &str = "savegame.ron";
+// Tests save and load too, they mustn't overwrite the player's game
+#[cfg(test)]
+const SAVE_PATH: &str = "target/test_savegame.ron";
+// Bump when the save format changes, older saves are refused
+const SAVE_VERSION: u32 = 8;
+
+#[derive(Event)]
+pub struct LoadGame;
+
+#[derive(Serialize, Deserialize)]
+struct SavedPlacement {
+    pos: (i32, i32),
+    object: WorldObject,
+    // Game minutes the crop or sapling had been growing when the game was saved
+    grown_for: Option<f64>,
+    // Soil moisture of flowerbeds
+    moisture: Option<f32>,
+    // Materials and work already put into a blueprint
+    blueprint: Option<Blueprint>,
+}
+
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    placements: Vec<SavedPlacement>,
+    coins: i32,
+    items: Vec<(InventoryObject, (bool, i32))>,
+    player: (f32, f32),
+    energy: f32,
+    colonists: Vec<(f32, f32)>,
+    // Game minutes on the clock
+    clock: f64,
+    // Every unfinished task, they get dispatched again after loading
+    tasks: Vec<Task>,
+}
+
+pub struct SavePlugin;
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LoadGame>()
+            .add_systems(OnEnter(AppState::InGame), load_game)
+            .add_systems(OnExit(AppState::InGame), save_game);
+    }
+}
+
+pub fn save_exists() -> bool {
+    Path::new(SAVE_PATH).exists()
+}
+
+fn read_save() -> Option<SaveFile> {
+    let contents = match fs::read_to_string(SAVE_PATH) {
+        Ok(contents) => contents,
+        Err(err) => {
+            warn!("Couldn't read save file: {}", err);
+            return None;
+        }
+    };
+
+    // Check the version first, so format changes don't show up as parse errors
+    match ron::from_str::<SaveHeader>(&contents) {
+        Ok(SaveHeader { version }) if version == SAVE_VERSION => {}
+        Ok(SaveHeader { version }) => {
+            warn!("Save file version {} isn't supported", version);
+            return None;
+        }
+        Err(err) => {
+            warn!("Couldn't parse save file: {}", err);
+            return None;
+        }
+    }
+
+    match ron::from_str::<SaveFile>(&contents) {
+        Ok(save) => Some(save),
+        Err(err) => {
+            warn!("Couldn't parse save file: {}", err);
+            None
+        }
+    }
+}
+
+// State kept on the placed objects besides what they are
+#[derive(SystemParam)]
+struct ObjectState<'w, 's> {
+    grow_query: Query<'w, 's, &'static GrowStartTime>,
+    moisture_query: Query<'w, 's, &'static Moisture>,
+    blueprint_query: Query<'w, 's, &'static Blueprint>,
+}
+
+fn save_game(
+    grid_query: Query<&Grid>,
+    inv_query: Query<&Inventory>,
+    player_query: Query<(&Transform, &Energy), With<Player>>,
+    colonist_query: Query<(&Transform, &Worker, &Schedule), With<Colonist>>,
+    queue: Res<TaskQueue>,
+    object_state: ObjectState,
+    clock: Res<GameClock>,
+) {
+    let ObjectState {
+        grow_query,
+        moisture_query,
+        blueprint_query,
+    } = object_state;
+    let grid = grid_query.single();
+
+    // Objects spanning several tiles are saved once, at their origin
+    let mut saved = HashSet::new();
+    let mut placements = vec![];
+    for placement in grid.placements() {
+        let layer = placement.object.layer();
+        if !saved.insert((placement.entity, layer)) {
+            continue;
+        }
+        let grown_for = match grow_query.get(placement.entity) {
+            Ok(grow_start_time) if layer == Layer::Object => {
+                Some(grow_start_time.grown_for(&clock))
+            }
+            _ => None,
+        };
+        let moisture = match moisture_query.get(placement.entity) {
+            Ok(moisture) if layer == Layer::Ground => Some(moisture.0),
+            _ => None,
+        };
+        placements.push(SavedPlacement {
+            pos: placement.origin,
+            object: placement.object,
+            grown_for,
+            moisture,
+            blueprint: blueprint_query.get(placement.entity).ok().copied(),
+        });
+    }
+
+    let inventory = inv_query.single();
+    let (player, energy) = player_query.single();
+    let player = player.translation;
+    let mut colonists = vec![];
+    let mut tasks = vec![];
+    for (transform, worker, schedule) in colonist_query.iter() {
+        colonists.push((transform.translation.x, transform.translation.y));
+        tasks.extend(worker.task().cloned());
+        tasks.extend(schedule.tasks.iter().cloned());
+    }
+    tasks.extend(queue.tasks.iter().cloned());
+    let save = SaveFile {
+        version: SAVE_VERSION,
+        placements,
+        coins: inventory.coins,
+        items: inventory.items.iter().map(|(k, v)| (*k, *v)).collect(),
+        player: (player.x, player.y),
+        energy: energy.0,
+        colonists,
+        clock: clock.elapsed_minutes(),
+        tasks,
+    };
+
+    match ron::ser::to_string_pretty(&save, PrettyConfig::default()) {
+        Ok(contents) => {
+            if let Err(err) = fs::write(SAVE_PATH, contents) {
+                error!("Couldn't write save file: {}", err);
+            }
+        }
+        Err(err) => error!("Couldn't serialize game: {}", err),
+    }
+}
+
+// Player and colonists, the colonists are spawned anew from the save
+#[derive(SystemParam)]
+struct Characters<'w, 's> {
+    player_query: Query<'w, 's, (&'static mut Transform, &'static mut Energy), With<Player>>,
+    colonist_query: Query<'w, 's, Entity, With<Colonist>>,
+    atlas: Res<'w, CharacterAtlas>,
+}
+
+// Clock, inventory and orders, everything saved that isn't on the map
+#[derive(SystemParam)]
+struct Progress<'w, 's> {
+    clock: ResMut<'w, GameClock>,
+    inv_query: Query<'w, 's, &'static mut Inventory>,
+    queue: ResMut<'w, TaskQueue>,
+}
+
+fn load_game(
+    mut load_events: EventReader<LoadGame>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    world_query: Query<Entity, With<WorldObject>>,
+    mut grid_query: Query<&mut Grid>,
+    characters: Characters,
+    progress: Progress,
+) {
+    let Characters {
+        mut player_query,
+        colonist_query,
+        atlas,
+    } = characters;
+    let Progress {
+        mut clock,
+        mut inv_query,
+        mut queue,
+    } = progress;
+    if load_events.read().count() == 0 {
+        return;
+    }
+    let Some(save) = read_save() else {
+        return;
+    };
+
+    // Replace the generated world with the saved one
+    for entity in world_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let mut grid = grid_query.single_mut();
+    grid.clear();
+    if let Some(selection) = grid.selection.take() {
+        commands.entity(selection.entity).despawn();
+    }
+
+    // Crops count their growth from the restored clock
+    *clock = GameClock::from_minutes(save.clock);
+
+    // Crops need the flowerbed below them, so restore layer by layer
+    for layer in [Layer::Ground, Layer::Object, Layer::Overlay] {
+        for placement in save.placements.iter().filter(|p| p.object.layer() == layer) {
+            let pos = grid.grid_to_world(placement.pos);
+            let entity = match placement.object {
+                WorldObject::Tree => spawn_tree(pos.extend(0.0), &mut commands, &asset_server),
+                WorldObject::Rock => spawn_rock(pos.extend(0.0), &mut commands, &asset_server),
+                WorldObject::Sapling => spawn_sapling(
+                    pos.extend(0.0),
+                    clock.elapsed_minutes() - placement.grown_for.unwrap_or(0.0),
+                    &mut commands,
+                    &asset_server,
+                ),
+                WorldObject::Grass => {
+                    spawn_grass_tile(pos.extend(0.0), &mut commands, &asset_server)
+                }
+                WorldObject::Flowerbed => {
+                    spawn_flowerbed(pos.extend(0.0), &mut commands, &asset_server)
+                }
+                WorldObject::Item(object) => {
+                    spawn_item(object, pos.extend(0.0), &mut commands, &asset_server)
+                }
+                WorldObject::House(house_part) => {
+                    spawn_house_part(house_part, pos.extend(0.0), &mut commands, &asset_server)
+                }
+                WorldObject::Blueprint(house_part) => spawn_blueprint(
+                    placement.blueprint.unwrap_or(Blueprint::new(house_part)),
+                    pos.extend(0.0),
+                    &mut commands,
+                    &asset_server,
+                ),
+                WorldObject::Crop(seed) => {
+                    let Some(flowerbed) = grid.get_object(pos, Layer::Ground) else {
+                        continue;
+                    };
+                    let grown_for = placement.grown_for.unwrap_or(0.0);
+                    plant_seed(
+                        seed,
+                        flowerbed.entity,
+                        pos,
+                        &mut commands,
+                        &asset_server,
+                        &mut grid,
+                        clock.elapsed_minutes() - grown_for,
+                    );
+                    continue;
+                }
+                WorldObject::DeadCrop => {
+                    let Some(flowerbed) = grid.get_object(pos, Layer::Ground) else {
+                        continue;
+                    };
+                    wither_crop(
+                        flowerbed.entity,
+                        pos,
+                        &mut commands,
+                        &asset_server,
+                        &mut grid,
+                    );
+                    continue;
+                }
+            };
+            if let Some(moisture) = placement.moisture {
+                commands.entity(entity).insert(Moisture(moisture));
+            }
+            if !place_spawned(entity, pos, placement.object, &mut commands, &mut grid) {
+                warn!(
+                    "Saved {:?} overlaps another object, leaving it out",
+                    placement.object
+                );
+            }
+        }
+    }
+
+    let mut inventory = inv_query.single_mut();
+    inventory.coins = save.coins;
+    for (object, item) in save.items {
+        inventory.items.insert(object, item);
+    }
+
+    let (mut player, mut energy) = player_query.single_mut();
+    player.translation.x = save.player.0;
+    player.translation.y = save.player.1;
+    energy.0 = save.energy;
+
+    for entity in colonist_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for (x, y) in save.colonists {
+        spawn_colonist(&mut commands, &atlas, Vec3::new(x, y, 0.0));
+    }
+    queue.tasks = save.tasks.into();
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::headless::{beans, HeadlessGame};
+    use crate::tasks::TaskType;
+
+    #[test]
+    fn loading_resumes_growth_and_orders() {
+        let mut game = HeadlessGame::new();
+        game.wait_for_assets();
+        let flowerbed = game.place(WorldObject::Flowerbed, (3, 3));
+        game.app.world.run_system_once(
+            move |mut commands: Commands,
+                  asset_server: Res<AssetServer>,
+                  mut grid_query: Query<&mut Grid>,
+                  clock: Res<GameClock>| {
+                let mut grid = grid_query.single_mut();
+                let pos = grid.grid_to_world((3, 3));
+                let planted = clock.elapsed_minutes() - 120.0;
+                plant_seed(
+                    beans(),
+                    flowerbed,
+                    pos,
+                    &mut commands,
+                    &asset_server,
+                    &mut grid,
+                    planted,
+                );
+            },
+        );
+        let task = Task::new(TaskType::CutTree, (6, 6));
+        game.app
+            .world
+            .resource_mut::<TaskQueue>()
+            .tasks
+            .push_back(task.clone());
+        game.app.world.run_system_once(save_game);
+
+        // Time passes and the order is forgotten, loading brings both back
+        game.skip(600.0);
+        game.app.world.resource_mut::<TaskQueue>().tasks.clear();
+        game.app.world.send_event(LoadGame);
+        game.app.world.run_system_once(load_game);
+
+        assert_eq!(
+            game.object_at((3, 3), Layer::Object),
+            Some(WorldObject::Crop(beans()))
+        );
+        let grid = game.grid();
+        let crop = grid.get_object(grid.grid_to_world((3, 3)), Layer::Object);
+        let crop = crop.unwrap().entity;
+        let clock = game.app.world.resource::<GameClock>();
+        let grown_for = game
+            .app
+            .world
+            .get::<GrowStartTime>(crop)
+            .unwrap()
+            .grown_for(clock);
+        assert!((grown_for - 120.0).abs() < 1e-6);
+        assert!(game.app.world.resource::<TaskQueue>().tasks == [task]);
+    }
+}
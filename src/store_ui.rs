@@ -1,9 +1,106 @@
+use crate::clock::GameClock;
+use crate::crops::Crops;
 use crate::menu_ui::{HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON};
 use crate::InventoryObject;
 use crate::{AppState, Inventory};
 
 use bevy::prelude::*;
 
+const IN_SEASON: Color = Color::rgb(0.4, 0.9, 0.4);
+const OUT_OF_SEASON: Color = Color::rgb(0.9, 0.4, 0.4);
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Category {
+    Seeds,
+    Materials,
+}
+
+impl Category {
+    fn name(&self) -> &'static str {
+        match self {
+            Category::Seeds => "Seeds",
+            Category::Materials => "Materials",
+        }
+    }
+}
+
+pub struct Product {
+    pub item: InventoryObject,
+    pub price: i32,
+    pub category: Category,
+    // None never runs out
+    pub stock: Option<i32>,
+}
+
+// Everything the store sells
+#[derive(Resource)]
+pub struct Catalog {
+    pub products: Vec<Product>,
+}
+
+impl Default for Catalog {
+    // Seeds are added once the crop definitions are loaded
+    fn default() -> Self {
+        Self {
+            products: vec![
+                Product {
+                    item: InventoryObject::Wood,
+                    price: 5,
+                    category: Category::Materials,
+                    stock: Some(50),
+                },
+                Product {
+                    item: InventoryObject::Rocks,
+                    price: 5,
+                    category: Category::Materials,
+                    stock: Some(50),
+                },
+            ],
+        }
+    }
+}
+
+impl Catalog {
+    // Adds the product, or updates its price if it's already sold
+    pub fn set_price(&mut self, item: InventoryObject, price: i32, category: Category) {
+        match self
+            .products
+            .iter_mut()
+            .find(|product| product.item == item)
+        {
+            Some(product) => product.price = price,
+            None => self.products.push(Product {
+                item,
+                price,
+                category,
+                stock: None,
+            }),
+        }
+    }
+
+    fn product(&self, item: InventoryObject) -> Option<&Product> {
+        self.products.iter().find(|product| product.item == item)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Quantity {
+    One,
+    Ten,
+    // As many as the coins and stock allow
+    Max,
+}
+
+impl Quantity {
+    fn label(&self) -> &'static str {
+        match self {
+            Quantity::One => "x1",
+            Quantity::Ten => "x10",
+            Quantity::Max => "Max",
+        }
+    }
+}
+
 #[derive(Component)]
 struct Store;
 
@@ -11,25 +108,36 @@ struct Store;
 struct ExitButton;
 
 #[derive(Component)]
-struct BeansButton;
+struct BuyButton(InventoryObject, Quantity);
 
+// Stock left of the product
 #[derive(Component)]
-struct PotatoesButton;
+struct StockText(InventoryObject);
 
 #[derive(Component)]
 pub struct CoinsText;
 
+#[derive(Component)]
+struct SellButton(InventoryObject);
+
+// Count of the item the player has left to sell
+#[derive(Component)]
+struct SellCountText(InventoryObject);
+
 pub struct StoreUiPlugin;
 impl Plugin for StoreUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Store), spawn_store)
+        app.init_resource::<Catalog>()
+            .add_systems(OnEnter(AppState::Store), spawn_store)
             .add_systems(
                 Update,
                 (
                     interact_with_exit_button,
-                    interact_with_beans_button,
-                    interact_with_potatoes_button,
-                    update_coins_text
+                    interact_with_buy_buttons,
+                    update_stock_text,
+                    update_coins_text,
+                    interact_with_sell_buttons,
+                    update_sell_count_text,
                 )
                     .run_if(in_state(AppState::Store)),
             )
@@ -37,11 +145,119 @@ impl Plugin for StoreUiPlugin {
     }
 }
 
+// Only way anything is bought, returns how many items were
+fn purchase(
+    catalog: &mut Catalog,
+    inventory: &mut Inventory,
+    item: InventoryObject,
+    quantity: Quantity,
+) -> i32 {
+    let Some(product) = catalog
+        .products
+        .iter_mut()
+        .find(|product| product.item == item)
+    else {
+        return 0;
+    };
+    let affordable = match product.price {
+        price if price > 0 => inventory.coins / price,
+        _ => i32::MAX,
+    };
+    let available = affordable.min(product.stock.unwrap_or(i32::MAX));
+    let count = match quantity {
+        Quantity::One => 1,
+        Quantity::Ten => 10,
+        Quantity::Max => available,
+    };
+    if count <= 0 || count > available {
+        return 0;
+    }
+
+    inventory.coins -= count * product.price;
+    if let Some(stock) = product.stock.as_mut() {
+        *stock -= count;
+    }
+    inventory
+        .items
+        .entry(item)
+        .and_modify(|(_, have)| *have += count)
+        .or_insert((false, count));
+    count
+}
+
+fn spawn_text(parent: &mut ChildBuilder, value: impl Into<String>, color: Color) {
+    parent.spawn(TextBundle::from_section(
+        value,
+        TextStyle {
+            font_size: 30.0,
+            color,
+            ..default()
+        },
+    ));
+}
+
+fn spawn_icon(parent: &mut ChildBuilder, texture: Handle<Image>, size: f32) {
+    parent.spawn(ImageBundle {
+        style: Style {
+            width: Val::Px(size),
+            height: Val::Px(size),
+            ..default()
+        },
+        image: UiImage::new(texture),
+        ..default()
+    });
+}
+
+fn row_bundle() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            height: Val::Px(60.0),
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Row,
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        border_color: Color::GRAY.into(),
+        ..default()
+    }
+}
+
+fn column_bundle(width: f32) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            height: Val::Percent(100.0),
+            width: Val::Percent(width),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Start,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    }
+}
+
 fn spawn_store(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    costs_query: Query<&Inventory>,
+    inv_query: Query<&Inventory>,
+    catalog: Res<Catalog>,
+    clock: Res<GameClock>,
+    crops: Crops,
 ) {
+    let inventory = inv_query.single();
+    let mut sellable: Vec<InventoryObject> = inventory
+        .items
+        .keys()
+        .filter(|object| inventory.prices.contains_key(object))
+        .copied()
+        .collect();
+    sellable.sort();
+
+    let mut products: Vec<&Product> = catalog.products.iter().collect();
+    products.sort_by_key(|product| (product.category, product.item));
+
     commands
         .spawn(NodeBundle {
             style: Style {
@@ -75,155 +291,112 @@ fn spawn_store(
                         align_self: AlignSelf::FlexEnd,
                         height: Val::Percent(100.0),
                         width: Val::Percent(100.0),
-                        flex_direction: FlexDirection::Column,
-                        justify_content: JustifyContent::Start,
-                        align_items: AlignItems::Center,
+                        flex_direction: FlexDirection::Row,
                         ..default()
                     },
                     background_color: Color::DARK_GRAY.into(),
                     ..default()
                 })
-                // Beans
+                // Buy
                 .with_children(|parent| {
-                    parent
-                        .spawn(NodeBundle {
-                            style: Style {
-                                align_self: AlignSelf::FlexEnd,
-                                height: Val::Percent(15.0),
-                                width: Val::Percent(100.0),
-                                flex_direction: FlexDirection::Row,
-                                justify_content: JustifyContent::SpaceBetween,
-                                align_items: AlignItems::Center,
-                                border: UiRect::all(Val::Px(1.0)),
-                                ..default()
-                            },
-                            border_color: Color::GRAY.into(),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn(ImageBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                image: UiImage::new(asset_server.load("beans.png")),
-                                ..default()
-                            });
-                        })
-                        .with_children(|parent| {
-                            parent.spawn(TextBundle::from_section(
-                                "Beans",
-                                TextStyle {
-                                    font_size: 30.0,
-                                    color: Color::WHITE,
-                                    ..default()
-                                },
-                            ));
-                        })
-                        .with_children(|parent| {
-                            parent
-                                .spawn((
-                                    ButtonBundle {
-                                        background_color: Color::BLACK.into(),
-                                        ..default()
-                                    },
-                                    BeansButton,
-                                ))
-                                .with_children(|parent| {
-                                    parent.spawn(ImageBundle {
-                                        style: Style {
-                                            width: Val::Px(25.0),
-                                            height: Val::Px(25.0),
-                                            ..default()
-                                        },
-                                        image: UiImage::new(asset_server.load("coins.png")),
-                                        ..default()
-                                    });
-                                })
-                                .with_children(|parent| {
-                                    parent.spawn(TextBundle::from_section(
-                                        costs_query.single().costs[&InventoryObject::Beans]
-                                            .to_string(),
+                    parent.spawn(column_bundle(60.0)).with_children(|parent| {
+                        spawn_text(parent, "Buy", Color::WHITE);
+                        let mut category = None;
+                        for product in products {
+                            if category != Some(product.category) {
+                                category = Some(product.category);
+                                spawn_text(parent, product.category.name(), Color::GRAY);
+                            }
+                            parent.spawn(row_bundle()).with_children(|parent| {
+                                spawn_icon(parent, asset_server.load(product.item.texture()), 50.0);
+                                spawn_text(parent, product.item.name(), Color::WHITE);
+                                // Seeds list the seasons they can be planted in
+                                if let Some(crop) = crops.get(product.item) {
+                                    let seasons: Vec<&str> =
+                                        crop.seasons.iter().map(|season| season.name()).collect();
+                                    let color = if crop.grows_in(clock.season()) {
+                                        IN_SEASON
+                                    } else {
+                                        OUT_OF_SEASON
+                                    };
+                                    spawn_text(parent, seasons.join("/"), color);
+                                }
+                                parent.spawn((
+                                    TextBundle::from_section(
+                                        "",
                                         TextStyle {
                                             font_size: 30.0,
-                                            color: Color::rgb(0.9, 0.9, 0.9),
+                                            color: Color::GRAY,
                                             ..default()
                                         },
-                                    ));
-                                });
-                        });
+                                    ),
+                                    StockText(product.item),
+                                ));
+                                spawn_icon(parent, asset_server.load("coins.png"), 25.0);
+                                spawn_text(
+                                    parent,
+                                    product.price.to_string(),
+                                    Color::rgb(0.9, 0.9, 0.9),
+                                );
+                                for quantity in [Quantity::One, Quantity::Ten, Quantity::Max] {
+                                    parent
+                                        .spawn((
+                                            ButtonBundle {
+                                                background_color: Color::BLACK.into(),
+                                                ..default()
+                                            },
+                                            BuyButton(product.item, quantity),
+                                        ))
+                                        .with_children(|parent| {
+                                            spawn_text(
+                                                parent,
+                                                quantity.label(),
+                                                Color::rgb(0.9, 0.9, 0.9),
+                                            );
+                                        });
+                                }
+                            });
+                        }
+                    });
                 })
-                // Potato seeds
+                // Sell
                 .with_children(|parent| {
-                    parent
-                        .spawn(NodeBundle {
-                            style: Style {
-                                align_self: AlignSelf::FlexEnd,
-                                height: Val::Percent(15.0),
-                                width: Val::Percent(100.0),
-                                flex_direction: FlexDirection::Row,
-                                justify_content: JustifyContent::SpaceBetween,
-                                align_items: AlignItems::Center,
-                                border: UiRect::all(Val::Px(1.0)),
-                                ..default()
-                            },
-                            border_color: Color::GRAY.into(),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn(ImageBundle {
-                                style: Style {
-                                    width: Val::Px(50.0),
-                                    height: Val::Px(50.0),
-                                    ..default()
-                                },
-                                image: UiImage::new(asset_server.load("potato_seeds.png")),
-                                ..default()
-                            });
-                        })
-                        .with_children(|parent| {
-                            parent.spawn(TextBundle::from_section(
-                                "Potato seeds",
-                                TextStyle {
-                                    font_size: 30.0,
-                                    color: Color::WHITE,
-                                    ..default()
-                                },
-                            ));
-                        })
-                        .with_children(|parent| {
-                            parent
-                                .spawn((
-                                    ButtonBundle {
-                                        background_color: Color::BLACK.into(),
-                                        ..default()
-                                    },
-                                    PotatoesButton,
-                                ))
-                                .with_children(|parent| {
-                                    parent.spawn(ImageBundle {
-                                        style: Style {
-                                            width: Val::Px(25.0),
-                                            height: Val::Px(25.0),
-                                            ..default()
-                                        },
-                                        image: UiImage::new(asset_server.load("coins.png")),
-                                        ..default()
-                                    });
-                                })
-                                .with_children(|parent| {
-                                    parent.spawn(TextBundle::from_section(
-                                        costs_query.single().costs[&InventoryObject::PotatoSeeds]
-                                            .to_string(),
+                    parent.spawn(column_bundle(40.0)).with_children(|parent| {
+                        spawn_text(parent, "Sell", Color::WHITE);
+                        for object in sellable {
+                            parent.spawn(row_bundle()).with_children(|parent| {
+                                spawn_icon(parent, asset_server.load(object.texture()), 50.0);
+                                spawn_text(parent, object.name(), Color::WHITE);
+                                parent.spawn((
+                                    TextBundle::from_section(
+                                        "0",
                                         TextStyle {
                                             font_size: 30.0,
-                                            color: Color::rgb(0.9, 0.9, 0.9),
+                                            color: Color::GRAY,
                                             ..default()
                                         },
-                                    ));
-                                });
-                        });
+                                    ),
+                                    SellCountText(object),
+                                ));
+                                parent
+                                    .spawn((
+                                        ButtonBundle {
+                                            background_color: Color::BLACK.into(),
+                                            ..default()
+                                        },
+                                        SellButton(object),
+                                    ))
+                                    .with_children(|parent| {
+                                        spawn_icon(parent, asset_server.load("coins.png"), 25.0);
+                                        spawn_text(
+                                            parent,
+                                            inventory.prices[&object].to_string(),
+                                            Color::rgb(0.9, 0.9, 0.9),
+                                        );
+                                    });
+                            });
+                        }
+                    });
                 });
         })
         // Right column
@@ -353,25 +526,33 @@ fn interact_with_exit_button(
     }
 }
 
-fn interact_with_beans_button(
+fn update_stock_text(mut text_query: Query<(&mut Text, &StockText)>, catalog: Res<Catalog>) {
+    for (mut text, stock_text) in text_query.iter_mut() {
+        text.sections[0].value = match catalog.product(stock_text.0).and_then(|p| p.stock) {
+            Some(stock) => stock.to_string(),
+            None => String::new(),
+        };
+    }
+}
+
+fn interact_with_buy_buttons(
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, With<BeansButton>),
+        (&Interaction, &mut BackgroundColor, &BuyButton),
+        Changed<Interaction>,
     >,
     mut inv_query: Query<&mut Inventory>,
+    mut catalog: ResMut<Catalog>,
 ) {
-    for (interaction, mut color) in &mut interaction_query {
+    for (interaction, mut color, buy_button) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
                 *color = PRESSED_BUTTON.into();
-                if inv_query.single_mut().coins >= 20 {
-                    inv_query
-                        .single_mut()
-                        .items
-                        .entry(InventoryObject::Beans)
-                        .and_modify(|(_, count)| *count += 1);
-                    inv_query.single_mut().coins -= 20;
-                }
+                purchase(
+                    &mut catalog,
+                    &mut inv_query.single_mut(),
+                    buy_button.0,
+                    buy_button.1,
+                );
             }
             Interaction::Hovered => {
                 *color = HOVERED_BUTTON.into();
@@ -383,24 +564,34 @@ fn interact_with_beans_button(
     }
 }
 
-fn interact_with_potatoes_button(
+fn update_sell_count_text(
+    mut text_query: Query<(&mut Text, &SellCountText)>,
+    inv_query: Query<&Inventory>,
+) {
+    let inventory = inv_query.single();
+    for (mut text, count_text) in text_query.iter_mut() {
+        text.sections[0].value = inventory.items[&count_text.0].1.to_string();
+    }
+}
+
+fn interact_with_sell_buttons(
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, With<PotatoesButton>),
+        (&Interaction, &mut BackgroundColor, &SellButton),
+        Changed<Interaction>,
     >,
     mut inv_query: Query<&mut Inventory>,
 ) {
-    for (interaction, mut color) in &mut interaction_query {
+    for (interaction, mut color, sell_button) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
                 *color = PRESSED_BUTTON.into();
-                if inv_query.single_mut().coins >= 10 {
-                    inv_query
-                        .single_mut()
+                let mut inventory = inv_query.single_mut();
+                if inventory.items[&sell_button.0].1 >= 1 {
+                    inventory
                         .items
-                        .entry(InventoryObject::PotatoSeeds)
-                        .and_modify(|(_, count)| *count += 1);
-                    inv_query.single_mut().coins -= 10;
+                        .entry(sell_button.0)
+                        .and_modify(|(_, count)| *count -= 1);
+                    inventory.coins += inventory.prices[&sell_button.0];
                 }
             }
             Interaction::Hovered => {
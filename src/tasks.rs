@@ -0,0 +1,738 @@
+use std::collections::VecDeque;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use pathfinding::prelude::astar;
+use serde::{Deserialize, Serialize};
+
+use crate::blueprints::{work_on_blueprint, Blueprint};
+use crate::clock::GameClock;
+use crate::crops::Crops;
+use crate::deconstruct::{deconstruct_part, DeconstructConfig, Deconstruction, DECONSTRUCT_WORK};
+use crate::hud_ui::House;
+use crate::player::{
+    set_idle_animation, set_walk_animation, set_work_animation, AnimationIndices, Movement, SPEED,
+};
+use crate::trees::sapling_fits;
+use crate::{
+    clear_crop, harvest_crop, hit_object, is_grown, place_spawned, plant_seed, spawn_flowerbed,
+    spawn_sapling, AppState, Damage, Grid, GrowStartTime, Inventory, InventoryObject, Layer,
+    Moisture, WorldObject,
+};
+
+// How far around the start and goal A* may wander, keeps unreachable goals from searching forever
+const SEARCH_MARGIN: i32 = 10;
+// Seconds between two swings of the tool
+const WORK_INTERVAL: f32 = 0.5;
+// Seconds a worker may spend on one path node before it gives up
+const STUCK_TIMEOUT: f32 = 3.0;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Pos(i32, i32);
+
+impl Pos {
+    fn distance(&self, other: &Pos) -> u32 {
+        self.0.abs_diff(other.0) + self.1.abs_diff(other.1)
+    }
+
+    fn successors(&self, grid: &Grid, goal: Pos, bounds: (Pos, Pos)) -> Vec<(Pos, u32)> {
+        let &Pos(x, y) = self;
+        let mut pos_vec: Vec<Pos> = vec![];
+        let pos_vec2: Vec<(i32, i32)> = vec![(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)];
+        for pos in pos_vec2.iter() {
+            if Pos(pos.0, pos.1) == goal {
+                pos_vec.push(Pos(pos.0, pos.1));
+                continue;
+            }
+            if pos.0 < bounds.0 .0
+                || pos.0 > bounds.1 .0
+                || pos.1 < bounds.0 .1
+                || pos.1 > bounds.1 .1
+            {
+                continue;
+            }
+            let pos2 = grid.get_object(grid.grid_to_world((pos.0, pos.1)), Layer::Object);
+            match pos2 {
+                Some(placement) if placement.object.blocks_movement() => {}
+                _ => pos_vec.push(Pos(pos.0, pos.1)),
+            }
+        }
+
+        pos_vec.into_iter().map(|p| (p, 1)).collect()
+    }
+}
+
+// Tiles to walk from start to goal, both ends included
+fn find_path(grid: &Grid, start: (i32, i32), goal: (i32, i32)) -> Option<Vec<Pos>> {
+    let bounds = (
+        Pos(
+            start.0.min(goal.0) - SEARCH_MARGIN,
+            start.1.min(goal.1) - SEARCH_MARGIN,
+        ),
+        Pos(
+            start.0.max(goal.0) + SEARCH_MARGIN,
+            start.1.max(goal.1) + SEARCH_MARGIN,
+        ),
+    );
+    let goal = Pos(goal.0, goal.1);
+    astar(
+        &Pos(start.0, start.1),
+        |p| p.successors(grid, goal, bounds),
+        |p| p.distance(&goal) / 3,
+        |p| *p == goal,
+    )
+    .map(|(path, _)| path)
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct TaskObject {
+    pub pos: Vec<(i32, i32)>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum TaskType {
+    CutTree,
+    MineRock,
+    DigFlowerbed,
+    PlantSeed(InventoryObject),
+    HarvestCrop,
+    WaterFlowerbed,
+    PlantSapling,
+    HaulItem,
+    BuildPart(House),
+    Deconstruct(House),
+}
+
+impl TaskType {
+    // Tool swung while working, None just stands there
+    fn tool(&self) -> Option<InventoryObject> {
+        match self {
+            TaskType::CutTree => Some(InventoryObject::Axe),
+            TaskType::MineRock => Some(InventoryObject::Pickaxe),
+            TaskType::DigFlowerbed
+            | TaskType::PlantSeed(_)
+            | TaskType::HarvestCrop
+            | TaskType::PlantSapling => Some(InventoryObject::Hoe),
+            TaskType::WaterFlowerbed => Some(InventoryObject::WateringCan),
+            TaskType::BuildPart(_) | TaskType::Deconstruct(_) => Some(InventoryObject::Hammer),
+            TaskType::HaulItem => None,
+        }
+    }
+
+    // Trees, rocks and buildings are worked on from the tile next to them
+    fn stands_on_target(&self) -> bool {
+        !matches!(
+            self,
+            TaskType::CutTree
+                | TaskType::MineRock
+                | TaskType::BuildPart(_)
+                | TaskType::Deconstruct(_)
+        )
+    }
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub task_type: TaskType,
+    pub task_object: TaskObject,
+}
+
+impl Task {
+    pub fn new(task_type: TaskType, pos: (i32, i32)) -> Self {
+        Self {
+            task_type,
+            task_object: TaskObject { pos: vec![pos] },
+        }
+    }
+
+    fn goal(&self) -> (i32, i32) {
+        self.task_object.pos[0]
+    }
+
+    // Whether there's still something to do at the goal, someone else might have done it
+    fn is_valid(&self, grid: &Grid) -> bool {
+        let pos = grid.grid_to_world(self.goal());
+        let ground = grid.get_object(pos, Layer::Ground).map(|p| p.object);
+        let object = grid.get_object(pos, Layer::Object).map(|p| p.object);
+        match self.task_type {
+            TaskType::CutTree => object == Some(WorldObject::Tree),
+            TaskType::MineRock => object == Some(WorldObject::Rock),
+            TaskType::DigFlowerbed => {
+                object.is_none() && matches!(ground, None | Some(WorldObject::Grass))
+            }
+            TaskType::PlantSeed(_) => object.is_none() && ground == Some(WorldObject::Flowerbed),
+            TaskType::HarvestCrop => {
+                matches!(object, Some(WorldObject::Crop(_) | WorldObject::DeadCrop))
+            }
+            TaskType::WaterFlowerbed => ground == Some(WorldObject::Flowerbed),
+            TaskType::PlantSapling => sapling_fits(grid, pos),
+            TaskType::HaulItem => !grid.is_layer_free(pos, Layer::Overlay),
+            TaskType::Deconstruct(house_part) => grid
+                .get_anchored(self.goal(), WorldObject::House(house_part))
+                .is_some(),
+            TaskType::BuildPart(house_part) => grid
+                .get_anchored(self.goal(), WorldObject::Blueprint(house_part))
+                .is_some(),
+        }
+    }
+}
+
+// Tasks assigned to one worker, carried out in order
+#[derive(Component, Default)]
+pub struct Schedule {
+    pub tasks: VecDeque<Task>,
+}
+
+// Tasks nobody has been assigned yet
+#[derive(Resource, Default)]
+pub struct TaskQueue {
+    pub tasks: VecDeque<Task>,
+}
+
+// Carries out tasks from the schedule, one at a time
+#[derive(Component)]
+pub struct Worker {
+    task: Option<Task>,
+    // Tiles left to walk, None until a path is planned
+    path: Option<VecDeque<Pos>>,
+    work_timer: Timer,
+    stuck_timer: Timer,
+}
+
+impl Default for Worker {
+    fn default() -> Self {
+        Self {
+            task: None,
+            path: None,
+            work_timer: Timer::from_seconds(WORK_INTERVAL, TimerMode::Repeating),
+            stuck_timer: Timer::from_seconds(STUCK_TIMEOUT, TimerMode::Once),
+        }
+    }
+}
+
+impl Worker {
+    pub fn is_busy(&self) -> bool {
+        self.task.is_some()
+    }
+
+    pub fn task(&self) -> Option<&Task> {
+        self.task.as_ref()
+    }
+
+    fn finish_task(&mut self) {
+        self.task = None;
+        self.path = None;
+    }
+}
+
+pub struct TasksPlugin;
+impl Plugin for TasksPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TaskQueue>().add_systems(
+            Update,
+            (queue_selected_tasks, dispatch_tasks, run_tasks)
+                .chain()
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+// Every task ordered so far, whether it's still queued or already handed to a worker
+#[derive(SystemParam)]
+pub struct PendingTasks<'w, 's> {
+    pub queue: ResMut<'w, TaskQueue>,
+    worker_query: Query<'w, 's, (&'static Worker, &'static Schedule)>,
+}
+
+impl PendingTasks<'_, '_> {
+    pub fn contains(&self, task: &Task) -> bool {
+        self.queue.tasks.contains(task)
+            || self.worker_query.iter().any(|(worker, schedule)| {
+                worker.task() == Some(task) || schedule.tasks.contains(task)
+            })
+    }
+
+    // Queues the task unless the same one was ordered already, false if it was
+    pub fn order(&mut self, task: Task) -> bool {
+        if self.contains(&task) {
+            return false;
+        }
+        self.queue.tasks.push_back(task);
+        true
+    }
+}
+
+// Crop definitions and what's needed to tell how far a crop has grown
+#[derive(SystemParam)]
+pub struct CropGrowth<'w, 's> {
+    pub crops: Crops<'w>,
+    pub clock: Res<'w, GameClock>,
+    pub grow_query: Query<'w, 's, &'static GrowStartTime>,
+}
+
+// Space over a selection orders whatever the active tool does to the tiles in it
+fn queue_selected_tasks(
+    inv_query: Query<&Inventory>,
+    mut grid_query: Query<&mut Grid>,
+    mut pending: PendingTasks,
+    growth: CropGrowth,
+    input: Res<Input<KeyCode>>,
+    mut commands: Commands,
+) {
+    let CropGrowth {
+        crops,
+        clock,
+        grow_query,
+    } = growth;
+    if !input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    let mut grid = grid_query.single_mut();
+    let Some(selection) = grid.selection.as_ref() else {
+        return;
+    };
+    commands.entity(selection.entity).despawn();
+    let tiles = grid.get_selected_tiles();
+    grid.selection = None;
+
+    let active = inv_query.single().active_object();
+    let task_types = match active {
+        Some(InventoryObject::Axe) => vec![TaskType::CutTree],
+        Some(InventoryObject::Pickaxe) => vec![TaskType::MineRock],
+        Some(InventoryObject::Hoe) => vec![TaskType::DigFlowerbed, TaskType::HarvestCrop],
+        Some(InventoryObject::WateringCan) => vec![TaskType::WaterFlowerbed],
+        Some(InventoryObject::Sapling) => vec![TaskType::PlantSapling],
+        Some(seed)
+            if crops
+                .get(seed)
+                .is_some_and(|crop| crop.grows_in(clock.season())) =>
+        {
+            vec![TaskType::PlantSeed(seed)]
+        }
+        _ => vec![],
+    };
+
+    // Blueprints left unbuilt, say for lack of materials, are ordered again from their origin
+    if active == Some(InventoryObject::Hammer) {
+        for pos in tiles.iter() {
+            let Some(placement) = grid.get_object(grid.grid_to_world(*pos), Layer::Object) else {
+                continue;
+            };
+            let WorldObject::Blueprint(house_part) = placement.object else {
+                continue;
+            };
+            pending.order(Task::new(TaskType::BuildPart(house_part), placement.origin));
+        }
+    }
+
+    for pos in tiles {
+        // Items lying around are hauled whatever the tool
+        for task_type in task_types.iter().chain([&TaskType::HaulItem]) {
+            let task = Task::new(task_type.clone(), pos);
+            // Don't order the same thing twice
+            if !task.is_valid(&grid) || pending.contains(&task) {
+                continue;
+            }
+            // Only fully grown crops are worth harvesting, withered ones get cleared
+            if task.task_type == TaskType::HarvestCrop {
+                let crop = grid.get_object(grid.grid_to_world(pos), Layer::Object);
+                if !crop.is_some_and(|crop| {
+                    crop.object == WorldObject::DeadCrop
+                        || is_grown(crop, &crops, &grow_query, &clock)
+                }) {
+                    continue;
+                }
+            }
+            pending.order(task);
+        }
+    }
+}
+
+// Hands every queued task to the nearest worker with nothing to do
+fn dispatch_tasks(
+    mut queue: ResMut<TaskQueue>,
+    grid_query: Query<&Grid>,
+    mut worker_query: Query<(&Worker, &mut Schedule, &Transform)>,
+) {
+    let grid = grid_query.single();
+    while let Some(task) = queue.tasks.front() {
+        let goal = grid.grid_to_world(task.task_object.pos[0]);
+        let nearest = worker_query
+            .iter_mut()
+            .filter(|(worker, schedule, _)| !worker.is_busy() && schedule.tasks.is_empty())
+            .min_by(|(_, _, a), (_, _, b)| {
+                let a = a.translation.truncate().distance_squared(goal);
+                let b = b.translation.truncate().distance_squared(goal);
+                a.total_cmp(&b)
+            });
+        let Some((_, mut schedule, _)) = nearest else {
+            break;
+        };
+        schedule.tasks.push_back(queue.tasks.pop_front().unwrap());
+    }
+}
+
+// Everything a worker needs to walk up to a task and be seen working on it
+type WorkerParts<'a> = (
+    &'a mut Worker,
+    &'a mut Schedule,
+    &'a Transform,
+    &'a mut KinematicCharacterController,
+    &'a mut AnimationIndices,
+    &'a mut TextureAtlasSprite,
+    &'a mut Movement,
+);
+
+// Objects the tasks are carried out on
+#[derive(SystemParam)]
+struct TaskTargets<'w, 's> {
+    object_query: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static mut Damage,
+            &'static WorldObject,
+        ),
+    >,
+    moisture_query: Query<'w, 's, &'static mut Moisture>,
+    blueprint_query: Query<'w, 's, &'static mut Blueprint>,
+    deconstruction_query: Query<'w, 's, &'static mut Deconstruction>,
+    deconstruct_config: Res<'w, DeconstructConfig>,
+    growth: CropGrowth<'w, 's>,
+}
+
+fn run_tasks(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut grid_query: Query<&mut Grid>,
+    mut inv_query: Query<&mut Inventory>,
+    mut worker_query: Query<WorkerParts, Without<Damage>>,
+    targets: TaskTargets,
+) {
+    let TaskTargets {
+        mut object_query,
+        mut moisture_query,
+        mut blueprint_query,
+        mut deconstruction_query,
+        deconstruct_config,
+        growth: CropGrowth {
+            crops,
+            clock,
+            grow_query,
+        },
+    } = targets;
+    let mut grid = grid_query.single_mut();
+    let mut inventory = inv_query.single_mut();
+    for (
+        mut worker,
+        mut schedule,
+        transform,
+        mut controller,
+        mut anim_indices,
+        mut sprite,
+        mut movement,
+    ) in worker_query.iter_mut()
+    {
+        if worker.task.is_none() {
+            worker.task = schedule.tasks.pop_front();
+        }
+        let Some(task) = worker.task.clone() else {
+            // Put the tool down once there's nothing left to do
+            if *movement == Movement::Working {
+                *movement = Movement::None;
+            }
+            set_idle_animation(&mut anim_indices, &mut sprite, &mut movement);
+            continue;
+        };
+        let goal = task.goal();
+        let goal_world = grid.grid_to_world(goal);
+
+        if !task.is_valid(&grid) {
+            worker.finish_task();
+            continue;
+        }
+
+        if worker.path.is_none() {
+            let start = grid.world_to_grid(transform.translation.truncate());
+            match find_path(&grid, start, goal) {
+                Some(path) => {
+                    let mut path: VecDeque<Pos> = path.into_iter().skip(1).collect();
+                    // Stop next to targets which can't be stood on
+                    if !task.task_type.stands_on_target() {
+                        path.pop_back();
+                        if let TaskType::BuildPart(house_part) | TaskType::Deconstruct(house_part) =
+                            task.task_type
+                        {
+                            let footprint = house_part.footprint();
+                            while path.back().is_some_and(|Pos(x, y)| {
+                                footprint.contains(&(x - goal.0, y - goal.1))
+                            }) {
+                                path.pop_back();
+                            }
+                        }
+                    }
+                    worker.path = Some(path);
+                    worker.stuck_timer.reset();
+                }
+                None => {
+                    warn!(
+                        "{:?} at {:?} can't be reached, dropping the task",
+                        task.task_type, goal
+                    );
+                    worker.finish_task();
+                    continue;
+                }
+            }
+        }
+
+        let next = worker.path.as_ref().and_then(|path| path.front().copied());
+        match next {
+            // Walk towards the next tile
+            Some(Pos(x, y)) => {
+                let delta = grid.grid_to_world((x, y)) - transform.translation.truncate();
+                let step = SPEED * time.delta_seconds();
+                if delta.length() <= step {
+                    controller.translation = Some(delta);
+                    worker.path.as_mut().unwrap().pop_front();
+                    worker.stuck_timer.reset();
+                } else {
+                    controller.translation = Some(delta.normalize() * step);
+                    worker.stuck_timer.tick(time.delta());
+                    if worker.stuck_timer.finished() {
+                        warn!(
+                            "Got stuck on the way to {:?} at {:?}, dropping the task",
+                            task.task_type, goal
+                        );
+                        worker.finish_task();
+                        continue;
+                    }
+                }
+
+                // Face the axis the worker mostly moves along
+                let (dx, dy) = if delta.x.abs() > delta.y.abs() {
+                    (delta.x.signum(), 0.0)
+                } else {
+                    (0.0, delta.y.signum())
+                };
+                set_walk_animation(dx, dy, &mut anim_indices, &mut sprite, &mut movement);
+            }
+            // Arrived, get to work
+            None => {
+                worker.work_timer.tick(time.delta());
+                if !worker.work_timer.just_finished() {
+                    continue;
+                }
+
+                // Turn to the target first
+                let delta = goal_world - transform.translation.truncate();
+                if delta.length() > 1.0 {
+                    let (dx, dy) = if delta.x.abs() > delta.y.abs() {
+                        (delta.x.signum(), 0.0)
+                    } else {
+                        (0.0, delta.y.signum())
+                    };
+                    set_walk_animation(dx, dy, &mut anim_indices, &mut sprite, &mut movement);
+                }
+                match task.task_type.tool() {
+                    Some(tool) => {
+                        set_work_animation(tool, &mut anim_indices, &mut sprite, &mut movement)
+                    }
+                    None => set_idle_animation(&mut anim_indices, &mut sprite, &mut movement),
+                }
+
+                let done = match task.task_type {
+                    TaskType::CutTree | TaskType::MineRock => {
+                        let target = grid.get_object(goal_world, Layer::Object).unwrap();
+                        match object_query.get_mut(target.entity) {
+                            Ok((transform, mut damage, object)) => hit_object(
+                                target.entity,
+                                transform,
+                                &mut damage,
+                                object,
+                                &mut commands,
+                                &asset_server,
+                                &mut grid,
+                            ),
+                            Err(_) => true,
+                        }
+                    }
+                    TaskType::DigFlowerbed => {
+                        // Grass is removed first, the flowerbed dug on the next swing
+                        match grid.remove_object(goal_world, Layer::Ground) {
+                            Some(grass) => {
+                                commands.entity(grass.entity).despawn();
+                                false
+                            }
+                            None => {
+                                let id = spawn_flowerbed(
+                                    goal_world.extend(0.0),
+                                    &mut commands,
+                                    &asset_server,
+                                );
+                                let flowerbed = WorldObject::Flowerbed;
+                                place_spawned(id, goal_world, flowerbed, &mut commands, &mut grid);
+                                true
+                            }
+                        }
+                    }
+                    TaskType::PlantSeed(seed) => {
+                        let in_season = crops
+                            .get(seed)
+                            .is_some_and(|crop| crop.grows_in(clock.season()));
+                        if !in_season {
+                            warn!("{:?} is out of season, dropping the task", seed);
+                        } else if inventory.items[&seed].1 >= 1 {
+                            let flowerbed = grid.get_object(goal_world, Layer::Ground).unwrap();
+                            plant_seed(
+                                seed,
+                                flowerbed.entity,
+                                goal_world,
+                                &mut commands,
+                                &asset_server,
+                                &mut grid,
+                                clock.elapsed_minutes(),
+                            );
+                            inventory
+                                .items
+                                .entry(seed)
+                                .and_modify(|(_, count)| *count -= 1);
+                        } else {
+                            warn!("Out of {:?}, dropping the task", seed);
+                        }
+                        true
+                    }
+                    TaskType::HarvestCrop => {
+                        let crop = grid.get_object(goal_world, Layer::Object).unwrap();
+                        let grown = is_grown(crop, &crops, &grow_query, &clock);
+                        if crop.object == WorldObject::DeadCrop {
+                            clear_crop(crop, &mut commands, &asset_server, &mut grid);
+                        } else if let (true, WorldObject::Crop(seed)) = (grown, crop.object) {
+                            harvest_crop(
+                                crop,
+                                crops.get(seed).unwrap(),
+                                &mut inventory,
+                                &mut commands,
+                                &asset_server,
+                                &mut grid,
+                            );
+                        }
+                        true
+                    }
+                    TaskType::WaterFlowerbed => {
+                        let flowerbed = grid.get_object(goal_world, Layer::Ground).unwrap();
+                        if let Ok(mut moisture) = moisture_query.get_mut(flowerbed.entity) {
+                            moisture.0 = 1.0;
+                        }
+                        true
+                    }
+                    TaskType::PlantSapling => {
+                        if inventory.items[&InventoryObject::Sapling].1 >= 1 {
+                            let id = spawn_sapling(
+                                goal_world.extend(0.0),
+                                clock.elapsed_minutes(),
+                                &mut commands,
+                                &asset_server,
+                            );
+                            let sapling = WorldObject::Sapling;
+                            if place_spawned(id, goal_world, sapling, &mut commands, &mut grid) {
+                                inventory
+                                    .items
+                                    .entry(InventoryObject::Sapling)
+                                    .and_modify(|(_, count)| *count -= 1);
+                            }
+                        } else {
+                            warn!("Out of saplings, dropping the task");
+                        }
+                        true
+                    }
+                    TaskType::HaulItem => {
+                        if let Some(item) = grid.remove_object(goal_world, Layer::Overlay) {
+                            if let WorldObject::Item(object) = item.object {
+                                inventory
+                                    .items
+                                    .entry(object)
+                                    .and_modify(|(_, count)| *count += 1);
+                            }
+                            commands.entity(item.entity).despawn_recursive();
+                        }
+                        true
+                    }
+                    TaskType::BuildPart(house_part) => {
+                        let target = grid
+                            .get_anchored(goal, WorldObject::Blueprint(house_part))
+                            .unwrap();
+                        match blueprint_query.get_mut(target.entity) {
+                            Ok(mut blueprint) => {
+                                if work_on_blueprint(&mut blueprint, &mut inventory) {
+                                    blueprint.is_built()
+                                } else {
+                                    warn!(
+                                        "Not enough material for {:?}, dropping the task",
+                                        house_part
+                                    );
+                                    true
+                                }
+                            }
+                            Err(_) => true,
+                        }
+                    }
+                    TaskType::Deconstruct(house_part) => {
+                        let target = grid
+                            .get_anchored(goal, WorldObject::House(house_part))
+                            .unwrap();
+                        let work = match deconstruction_query.get_mut(target.entity) {
+                            Ok(mut deconstruction) => {
+                                deconstruction.work += 1;
+                                deconstruction.work
+                            }
+                            // Orders restored from a save start over on an unmarked part
+                            Err(_) => {
+                                commands
+                                    .entity(target.entity)
+                                    .insert(Deconstruction { work: 1 });
+                                1
+                            }
+                        };
+                        if work >= DECONSTRUCT_WORK {
+                            deconstruct_part(
+                                target,
+                                deconstruct_config.refund,
+                                &inventory,
+                                &mut commands,
+                                &asset_server,
+                                &mut grid,
+                            );
+                        }
+                        work >= DECONSTRUCT_WORK
+                    }
+                };
+                if done {
+                    worker.finish_task();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessGame;
+
+    #[test]
+    fn colonists_cut_queued_tree() {
+        let mut game = HeadlessGame::new();
+        game.place(WorldObject::Tree, (4, 4));
+        game.app
+            .world
+            .resource_mut::<TaskQueue>()
+            .tasks
+            .push_back(Task::new(TaskType::CutTree, (4, 4)));
+
+        let cut = game.step_until(1200, |game| game.object_at((4, 4), Layer::Object).is_none());
+        assert!(cut);
+    }
+}
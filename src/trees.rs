@@ -0,0 +1,277 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::{seq::IteratorRandom, seq::SliceRandom, Rng};
+
+use crate::clock::GameClock;
+use crate::player::PlayerHands;
+use crate::{
+    drop_item, place_spawned, spawn_sapling, spawn_tree, AppState, Grid, GrowStartTime, Inventory,
+    InventoryObject, Layer, WorldObject,
+};
+
+// Game minutes a sapling takes to grow into a tree, two days
+const SAPLING_GROW_MINUTES: f64 = 2880.0;
+
+#[derive(Resource)]
+pub struct ForestConfig {
+    // Chance of a felled tree dropping a sapling next to its wood
+    pub sapling_chance: f64,
+    // Game minutes between wild trees seeding a sapling nearby, on average
+    pub spread_minutes: f64,
+}
+
+impl Default for ForestConfig {
+    fn default() -> Self {
+        Self {
+            sapling_chance: 0.3,
+            spread_minutes: 360.0,
+        }
+    }
+}
+
+// Sent by hit_object when a tree comes down
+#[derive(Event)]
+pub struct TreeFelled(pub Vec2);
+
+pub struct TreesPlugin;
+impl Plugin for TreesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ForestConfig>()
+            .add_event::<TreeFelled>()
+            .add_systems(
+                Update,
+                (drop_saplings, plant_sapling, grow_saplings, spread_forest)
+                    .run_if(in_state(AppState::InGame)),
+            );
+    }
+}
+
+// Saplings take root on bare soil or grass, flowerbeds are for crops
+pub fn sapling_fits(grid: &Grid, pos: Vec2) -> bool {
+    grid.is_layer_free(pos, Layer::Object)
+        && grid.is_layer_free(pos, Layer::Overlay)
+        && matches!(
+            grid.get_object(pos, Layer::Ground)
+                .map(|ground| ground.object),
+            None | Some(WorldObject::Grass)
+        )
+}
+
+fn drop_saplings(
+    mut felled: EventReader<TreeFelled>,
+    config: Res<ForestConfig>,
+    mut grid_query: Query<&mut Grid>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    let mut rng = rand::thread_rng();
+    let mut grid = grid_query.single_mut();
+    for TreeFelled(pos) in felled.read() {
+        if !rng.gen_bool(config.sapling_chance) {
+            continue;
+        }
+        // Wood takes the tree's own tile, the sapling lands next to it
+        let (x, y) = grid.world_to_grid(*pos);
+        let mut neighbours = [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)];
+        neighbours.shuffle(&mut rng);
+        let free = neighbours
+            .into_iter()
+            .map(|tile| grid.grid_to_world(tile))
+            .find(|tile| {
+                grid.is_layer_free(*tile, Layer::Object)
+                    && grid.is_layer_free(*tile, Layer::Overlay)
+            });
+        if let Some(tile) = free {
+            let item = InventoryObject::Sapling;
+            drop_item(item, tile, &mut commands, &asset_server, &mut grid);
+        }
+    }
+}
+
+fn plant_sapling(
+    input: Res<Input<KeyCode>>,
+    mut inv_query: Query<&mut Inventory>,
+    mut grid_query: Query<&mut Grid>,
+    mut hands: PlayerHands,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    clock: Res<GameClock>,
+) {
+    let mut inventory = inv_query.single_mut();
+    if !input.just_pressed(KeyCode::Space)
+        || inventory.active_object() != Some(InventoryObject::Sapling)
+        || inventory.items[&InventoryObject::Sapling].1 < 1
+    {
+        return;
+    }
+    let mut grid = grid_query.single_mut();
+    let player_vec2 = hands.pos();
+    if !sapling_fits(&grid, player_vec2) || hands.too_tired() {
+        return;
+    }
+
+    let pos = grid.grid_to_world(grid.world_to_grid(player_vec2));
+    let id = spawn_sapling(
+        pos.extend(0.0),
+        clock.elapsed_minutes(),
+        &mut commands,
+        &asset_server,
+    );
+    if !place_spawned(id, pos, WorldObject::Sapling, &mut commands, &mut grid) {
+        return;
+    }
+    inventory
+        .items
+        .entry(InventoryObject::Sapling)
+        .and_modify(|(_, count)| *count -= 1);
+
+    hands.work(InventoryObject::Hoe);
+}
+
+fn grow_saplings(
+    sapling_query: Query<(Entity, &Transform, &GrowStartTime, &WorldObject)>,
+    character_query: Query<&Transform, With<KinematicCharacterController>>,
+    mut grid_query: Query<&mut Grid>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    clock: Res<GameClock>,
+) {
+    let mut grid = grid_query.single_mut();
+    for (entity, transform, grow_start_time, object) in sapling_query.iter() {
+        if *object != WorldObject::Sapling
+            || grow_start_time.grown_for(&clock) < SAPLING_GROW_MINUTES
+        {
+            continue;
+        }
+        // A tree's collider would trap whoever stands there, wait until they step off
+        let tile = grid.world_to_grid(transform.translation.truncate());
+        if character_query
+            .iter()
+            .any(|character| grid.world_to_grid(character.translation.truncate()) == tile)
+        {
+            continue;
+        }
+
+        let pos = grid.grid_to_world(tile);
+        commands.entity(entity).despawn_recursive();
+        grid.remove_object(pos, Layer::Object);
+        let id = spawn_tree(pos.extend(0.0), &mut commands, &asset_server);
+        place_spawned(id, pos, WorldObject::Tree, &mut commands, &mut grid);
+    }
+}
+
+// Now and then a wild tree seeds a sapling on an empty tile around it
+fn spread_forest(
+    config: Res<ForestConfig>,
+    tree_query: Query<(&Transform, &WorldObject)>,
+    character_query: Query<&Transform, With<KinematicCharacterController>>,
+    mut grid_query: Query<&mut Grid>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    clock: Res<GameClock>,
+) {
+    let mut rng = rand::thread_rng();
+    let chance = (clock.delta_minutes() / config.spread_minutes).clamp(0.0, 1.0);
+    if !rng.gen_bool(chance) {
+        return;
+    }
+    let tree = tree_query
+        .iter()
+        .filter(|(_, object)| **object == WorldObject::Tree)
+        .choose(&mut rng);
+    let Some((transform, _)) = tree else {
+        return;
+    };
+
+    let mut grid = grid_query.single_mut();
+    let (x, y) = grid.world_to_grid(transform.translation.truncate());
+    let tile = (x + rng.gen_range(-1..=1), y + rng.gen_range(-1..=1));
+    let pos = grid.grid_to_world(tile);
+    let occupied = character_query
+        .iter()
+        .any(|character| grid.world_to_grid(character.translation.truncate()) == tile);
+    if occupied || !sapling_fits(&grid, pos) {
+        return;
+    }
+    let id = spawn_sapling(
+        pos.extend(0.0),
+        clock.elapsed_minutes(),
+        &mut commands,
+        &asset_server,
+    );
+    place_spawned(id, pos, WorldObject::Sapling, &mut commands, &mut grid);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessGame;
+
+    #[test]
+    fn felled_tree_drops_sapling() {
+        let mut game = HeadlessGame::new();
+        game.app.world.resource_mut::<ForestConfig>().sapling_chance = 1.0;
+        game.place(WorldObject::Tree, (0, 1));
+        // Rocks leave the player's tile as the only free one around the tree
+        for tile in [(1, 1), (-1, 1), (0, 2)] {
+            game.place(WorldObject::Rock, tile);
+        }
+        game.press(KeyCode::Key1);
+        for _ in 0..3 {
+            game.press(KeyCode::Space);
+        }
+
+        // Landing under the player's feet, it's picked up right away
+        let picked_up = game.step_until(10, |game| game.count(InventoryObject::Sapling) == 1);
+        assert!(picked_up);
+        assert_eq!(game.object_at((0, 0), Layer::Overlay), None);
+        assert_eq!(
+            game.object_at((0, 1), Layer::Overlay),
+            Some(WorldObject::Item(InventoryObject::Wood))
+        );
+    }
+
+    #[test]
+    fn sapling_grows_into_tree() {
+        let mut game = HeadlessGame::new();
+        game.inventory()
+            .items
+            .insert(InventoryObject::Sapling, (false, 1));
+        // Hotbar: Axe, Pickaxe, Hoe, Watering can, Hammer, Sapling
+        game.press(KeyCode::Key6);
+        game.press(KeyCode::Space);
+        assert_eq!(
+            game.object_at((0, 0), Layer::Object),
+            Some(WorldObject::Sapling)
+        );
+        assert_eq!(game.count(InventoryObject::Sapling), 0);
+
+        // Grown, but the player is still in the way
+        game.skip(1500.0);
+        assert_eq!(
+            game.object_at((0, 0), Layer::Object),
+            Some(WorldObject::Sapling)
+        );
+
+        game.set_player_tile((3, 3));
+        game.step(1);
+        assert_eq!(
+            game.object_at((0, 0), Layer::Object),
+            Some(WorldObject::Tree)
+        );
+    }
+
+    #[test]
+    fn forest_spreads_to_empty_tiles() {
+        let mut game = HeadlessGame::new();
+        game.app.world.resource_mut::<ForestConfig>().spread_minutes = 1.0;
+        game.place(WorldObject::Tree, (5, 5));
+
+        let spread = game.step_until(600, |game| {
+            (4..=6).any(|x| {
+                (4..=6).any(|y| game.object_at((x, y), Layer::Object) == Some(WorldObject::Sapling))
+            })
+        });
+        assert!(spread);
+    }
+}
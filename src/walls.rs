@@ -0,0 +1,196 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{cuboids, AppState, Grid, Layer, WorldObject, TILE, TILE_HALF};
+
+// Half the thickness of a wall, it runs through the middle of its tile
+const WALL_HALF: f32 = 15.0;
+// Sides a wall can join up on, one bit each
+const NORTH: u8 = 1;
+const EAST: u8 = 2;
+const SOUTH: u8 = 4;
+const WEST: u8 = 8;
+
+// Sides of a wall with a house part next to them, the wall's sprite and collider reach out to those
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct WallLinks(u8);
+
+impl WallLinks {
+    // Post on its own, an end, a straight run, a corner, a T-junction or a crossing
+    pub fn texture(&self) -> String {
+        let sides: String = "nesw"
+            .chars()
+            .enumerate()
+            .filter(|(bit, _)| self.0 & 1 << bit != 0)
+            .map(|(_, side)| side)
+            .collect();
+        if sides.is_empty() {
+            "wall_post.png".to_string()
+        } else {
+            format!("wall_{}.png", sides)
+        }
+    }
+
+    // Post in the middle of the tile and an arm out to the edge on each joined side
+    pub fn colliders(&self) -> Vec<(Vec2, Vec2)> {
+        let center = Vec2::new(0.0, -TILE);
+        let arm = (TILE_HALF - WALL_HALF) / 2.0;
+        let mut colliders = vec![(center, Vec2::splat(WALL_HALF))];
+        for (side, direction) in [
+            (NORTH, Vec2::Y),
+            (EAST, Vec2::X),
+            (SOUTH, Vec2::NEG_Y),
+            (WEST, Vec2::NEG_X),
+        ] {
+            if self.0 & side == 0 {
+                continue;
+            }
+            let half = if direction.x == 0.0 {
+                Vec2::new(WALL_HALF, arm)
+            } else {
+                Vec2::new(arm, WALL_HALF)
+            };
+            colliders.push((center + direction * (WALL_HALF + arm), half));
+        }
+        colliders
+    }
+}
+
+pub struct WallsPlugin;
+impl Plugin for WallsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, tile_walls.run_if(in_state(AppState::InGame)));
+    }
+}
+
+fn neighbours((x, y): (i32, i32)) -> [(u8, (i32, i32)); 4] {
+    [
+        (NORTH, (x, y + 1)),
+        (EAST, (x + 1, y)),
+        (SOUTH, (x, y - 1)),
+        (WEST, (x - 1, y)),
+    ]
+}
+
+// Doors count too, the wall runs up to the frame
+fn wall_links(grid: &Grid, tile: (i32, i32)) -> WallLinks {
+    let mut links = 0;
+    for (side, neighbour) in neighbours(tile) {
+        let part = grid.get_object(grid.grid_to_world(neighbour), Layer::Object);
+        if part.is_some_and(|part| matches!(part.object, WorldObject::House(_))) {
+            links |= side;
+        }
+    }
+    WallLinks(links)
+}
+
+// Joins the walls around every part put up or taken down to what's next to them now
+fn tile_walls(
+    part_query: Query<(Entity, &WorldObject, &Transform), Changed<WorldObject>>,
+    mut removed: RemovedComponents<WorldObject>,
+    // Tiles of the parts standing, a part that's gone has nothing left to say where it was
+    mut part_tiles: Local<HashMap<Entity, Vec<(i32, i32)>>>,
+    grid_query: Query<&Grid>,
+    mut wall_query: Query<(&mut WallLinks, &mut Handle<Image>, &mut Collider)>,
+    asset_server: Res<AssetServer>,
+) {
+    let grid = grid_query.single();
+    let mut changed = HashSet::new();
+    for (entity, object, transform) in part_query.iter() {
+        let WorldObject::House(house_part) = object else {
+            continue;
+        };
+        let origin = grid.world_to_grid(transform.translation.truncate());
+        let tiles: Vec<(i32, i32)> = house_part
+            .footprint()
+            .into_iter()
+            .map(|(x, y)| (origin.0 + x, origin.1 + y))
+            .collect();
+        changed.extend(tiles.iter().copied());
+        part_tiles.insert(entity, tiles);
+    }
+    for entity in removed.read() {
+        changed.extend(part_tiles.remove(&entity).into_iter().flatten());
+    }
+
+    let mut walls = HashSet::new();
+    for tile in changed {
+        walls.insert(tile);
+        walls.extend(neighbours(tile).map(|(_, neighbour)| neighbour));
+    }
+    for tile in walls {
+        let Some(wall) = grid.get_object(grid.grid_to_world(tile), Layer::Object) else {
+            continue;
+        };
+        let Ok((mut links, mut texture, mut collider)) = wall_query.get_mut(wall.entity) else {
+            continue;
+        };
+        let joined = wall_links(grid, tile);
+        if *links != joined {
+            *links = joined;
+            *texture = asset_server.load(joined.texture());
+            *collider = cuboids(joined.colliders());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::headless::HeadlessGame;
+    use crate::hud_ui::House;
+
+    fn links(game: &mut HeadlessGame, wall: Entity) -> WallLinks {
+        *game.app.world.get::<WallLinks>(wall).unwrap()
+    }
+
+    #[test]
+    fn walls_join_the_parts_around_them() {
+        let mut game = HeadlessGame::new();
+        // Parts stand on the tile below where they're placed
+        let corner = game.place(WorldObject::House(House::Wall), (3, 4));
+        let east = game.place(WorldObject::House(House::Wall), (4, 4));
+        game.place(WorldObject::House(House::Door), (3, 3));
+        game.step(1);
+        assert_eq!(links(&mut game, corner), WallLinks(EAST | SOUTH));
+        assert_eq!(links(&mut game, east), WallLinks(WEST));
+        assert_eq!(links(&mut game, corner).texture(), "wall_es.png");
+
+        let north = game.place(WorldObject::House(House::Wall), (3, 5));
+        let west = game.place(WorldObject::House(House::Wall), (2, 4));
+        game.step(1);
+        assert_eq!(
+            links(&mut game, corner),
+            WallLinks(NORTH | EAST | SOUTH | WEST)
+        );
+        assert_eq!(links(&mut game, north), WallLinks(SOUTH));
+        assert_eq!(links(&mut game, west), WallLinks(EAST));
+        assert_eq!(links(&mut game, corner).colliders().len(), 5);
+    }
+
+    #[test]
+    fn taking_a_wall_down_rejoins_its_neighbours() {
+        let mut game = HeadlessGame::new();
+        let lower = game.place(WorldObject::House(House::Wall), (3, 3));
+        let upper = game.place(WorldObject::House(House::Wall), (3, 4));
+        game.step(1);
+        assert_eq!(links(&mut game, upper), WallLinks(SOUTH));
+        assert_eq!(links(&mut game, lower), WallLinks(NORTH));
+
+        game.app.world.run_system_once(
+            move |mut commands: Commands, mut grid_query: Query<&mut Grid>| {
+                commands.entity(lower).despawn_recursive();
+                let mut grid = grid_query.single_mut();
+                let pos = grid.grid_to_world((3, 2));
+                grid.remove_object(pos, Layer::Object);
+            },
+        );
+        game.step(1);
+        assert_eq!(links(&mut game, upper), WallLinks::default());
+        assert_eq!(links(&mut game, upper).texture(), "wall_post.png");
+    }
+}
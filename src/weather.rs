@@ -0,0 +1,324 @@
+use bevy::prelude::*;
+use rand::{seq::IteratorRandom, Rng};
+
+use crate::clock::GameClock;
+use crate::{hit_object, AppState, Damage, Grid, MainCamera, Moisture, WorldObject};
+
+// Game minutes a spell of weather lasts at least and at most
+const MIN_SPELL: f64 = 180.0;
+const MAX_SPELL: f64 = 480.0;
+// Game minutes between trees being hit during a storm, on average
+const STORM_HIT_MINUTES: f64 = 60.0;
+// Size of the area rain is drawn over, a bit more than the window
+const RAIN_AREA: Vec2 = Vec2::new(1400.0, 800.0);
+const RAIN_SPEED: Vec2 = Vec2::new(-60.0, -600.0);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Weather {
+    Clear,
+    Cloudy,
+    Rain,
+    Storm,
+}
+
+impl Weather {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weather::Clear => "Clear",
+            Weather::Cloudy => "Cloudy",
+            Weather::Rain => "Rain",
+            Weather::Storm => "Storm",
+        }
+    }
+
+    // Weather that can follow this one, with their weights
+    fn next(&self) -> &'static [(Weather, u32)] {
+        match self {
+            Weather::Clear => &[(Weather::Clear, 5), (Weather::Cloudy, 3)],
+            Weather::Cloudy => &[
+                (Weather::Clear, 3),
+                (Weather::Cloudy, 2),
+                (Weather::Rain, 3),
+                (Weather::Storm, 1),
+            ],
+            Weather::Rain => &[
+                (Weather::Cloudy, 3),
+                (Weather::Rain, 2),
+                (Weather::Storm, 1),
+            ],
+            Weather::Storm => &[(Weather::Cloudy, 2), (Weather::Rain, 2)],
+        }
+    }
+
+    fn is_wet(&self) -> bool {
+        matches!(self, Weather::Rain | Weather::Storm)
+    }
+
+    fn rain_drops(&self) -> usize {
+        match self {
+            Weather::Rain => 150,
+            Weather::Storm => 300,
+            _ => 0,
+        }
+    }
+
+    // How much the clouds darken the view
+    fn gloom(&self) -> f32 {
+        match self {
+            Weather::Clear => 0.0,
+            Weather::Cloudy => 0.15,
+            Weather::Rain => 0.25,
+            Weather::Storm => 0.4,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct WeatherState {
+    pub current: Weather,
+    // Weather coming next, already decided so it can be shown
+    pub forecast: Weather,
+    // Game minutes on the clock when the forecast arrives, None until the first spell starts
+    changes_at: Option<f64>,
+}
+
+impl Default for WeatherState {
+    fn default() -> Self {
+        Self {
+            current: Weather::Clear,
+            forecast: roll(Weather::Clear.next()),
+            changes_at: None,
+        }
+    }
+}
+
+impl WeatherState {
+    // Weather that never changes, so tests aren't rained on by chance
+    #[cfg(test)]
+    pub fn steady(weather: Weather) -> Self {
+        Self {
+            current: weather,
+            forecast: weather,
+            changes_at: Some(f64::INFINITY),
+        }
+    }
+}
+
+fn roll(choices: &[(Weather, u32)]) -> Weather {
+    let total: u32 = choices.iter().map(|(_, weight)| weight).sum();
+    let mut pick = rand::thread_rng().gen_range(0..total);
+    for (weather, weight) in choices {
+        if pick < *weight {
+            return *weather;
+        }
+        pick -= weight;
+    }
+    choices[0].0
+}
+
+#[derive(Component)]
+struct RainDrop;
+
+#[derive(Component)]
+struct CloudOverlay;
+
+pub struct WeatherPlugin;
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WeatherState>()
+            .add_systems(PostStartup, spawn_cloud_overlay)
+            .add_systems(
+                Update,
+                (
+                    change_weather,
+                    wet_flowerbeds,
+                    storm_damage,
+                    spawn_rain,
+                    fall_rain,
+                    darken_clouds,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::InGame)),
+            );
+    }
+}
+
+fn spawn_cloud_overlay(mut commands: Commands, camera_query: Query<Entity, With<MainCamera>>) {
+    let overlay = commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(0.2, 0.2, 0.25, 0.0),
+                    custom_size: Some(Vec2::new(3000.0, 3000.0)),
+                    ..default()
+                },
+                // Below the night overlay, so nights aren't darker when it's cloudy
+                transform: Transform::from_xyz(0.0, 0.0, -1.2),
+                ..default()
+            },
+            CloudOverlay,
+        ))
+        .id();
+    commands.entity(camera_query.single()).add_child(overlay);
+}
+
+fn change_weather(mut weather: ResMut<WeatherState>, clock: Res<GameClock>) {
+    let now = clock.elapsed_minutes();
+    match weather.changes_at {
+        Some(changes_at) if now < changes_at => return,
+        Some(_) => {
+            weather.current = weather.forecast;
+            weather.forecast = roll(weather.current.next());
+        }
+        // The game starts with the weather it already has
+        None => {}
+    }
+    weather.changes_at = Some(now + rand::thread_rng().gen_range(MIN_SPELL..MAX_SPELL));
+}
+
+fn wet_flowerbeds(weather: Res<WeatherState>, mut moisture_query: Query<&mut Moisture>) {
+    if !weather.current.is_wet() {
+        return;
+    }
+    for mut moisture in moisture_query.iter_mut() {
+        moisture.0 = 1.0;
+    }
+}
+
+// Storms now and then hit a tree, which can break it like an axe would
+fn storm_damage(
+    weather: Res<WeatherState>,
+    clock: Res<GameClock>,
+    mut tree_query: Query<(Entity, &Transform, &mut Damage, &WorldObject)>,
+    mut grid_query: Query<&mut Grid>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    if weather.current != Weather::Storm {
+        return;
+    }
+    let mut rng = rand::thread_rng();
+    let chance = (clock.delta_minutes() / STORM_HIT_MINUTES).min(1.0);
+    if !rng.gen_bool(chance) {
+        return;
+    }
+    let tree = tree_query
+        .iter_mut()
+        .filter(|(_, _, _, object)| **object == WorldObject::Tree)
+        .choose(&mut rng);
+    if let Some((entity, transform, mut damage, object)) = tree {
+        hit_object(
+            entity,
+            transform,
+            &mut damage,
+            object,
+            &mut commands,
+            &asset_server,
+            &mut grid_query.single_mut(),
+        );
+    }
+}
+
+// Keeps as many drops around as the weather asks for
+fn spawn_rain(
+    weather: Res<WeatherState>,
+    mut commands: Commands,
+    camera_query: Query<Entity, With<MainCamera>>,
+    drop_query: Query<Entity, With<RainDrop>>,
+) {
+    let wanted = weather.current.rain_drops();
+    let count = drop_query.iter().count();
+    if count > wanted {
+        for entity in drop_query.iter().skip(wanted) {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+    let camera = camera_query.single();
+    for _ in count..wanted {
+        let x = rng.gen_range(-RAIN_AREA.x / 2.0..RAIN_AREA.x / 2.0);
+        let y = rng.gen_range(-RAIN_AREA.y / 2.0..RAIN_AREA.y / 2.0);
+        let drop = commands
+            .spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgba(0.6, 0.7, 1.0, 0.6),
+                        custom_size: Some(Vec2::new(2.0, 14.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(x, y, -1.5),
+                    ..default()
+                },
+                RainDrop,
+            ))
+            .id();
+        commands.entity(camera).add_child(drop);
+    }
+}
+
+fn fall_rain(time: Res<Time>, mut drop_query: Query<&mut Transform, With<RainDrop>>) {
+    for mut transform in drop_query.iter_mut() {
+        transform.translation += (RAIN_SPEED * time.delta_seconds()).extend(0.0);
+        // Drops leaving the view come back in at the top
+        if transform.translation.y < -RAIN_AREA.y / 2.0 {
+            transform.translation.y += RAIN_AREA.y;
+        }
+        if transform.translation.x < -RAIN_AREA.x / 2.0 {
+            transform.translation.x += RAIN_AREA.x;
+        }
+    }
+}
+
+fn darken_clouds(
+    weather: Res<WeatherState>,
+    mut overlay_query: Query<&mut Sprite, With<CloudOverlay>>,
+) {
+    for mut sprite in overlay_query.iter_mut() {
+        sprite.color.set_a(weather.current.gloom());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{bean_pods, beans, HeadlessGame};
+    use crate::{InventoryObject, Layer};
+
+    #[test]
+    fn rain_waters_flowerbeds() {
+        let mut game = HeadlessGame::new();
+        game.wait_for_assets();
+        game.place(WorldObject::Flowerbed, (0, 0));
+        game.inventory().items.insert(beans(), (false, 1));
+        game.press(KeyCode::Key6);
+        game.press(KeyCode::Space);
+
+        game.app
+            .insert_resource(WeatherState::steady(Weather::Rain));
+        game.step(1);
+        game.skip(600.0);
+        game.press(KeyCode::Key3);
+        game.press(KeyCode::Space);
+        assert_eq!(game.count(bean_pods()), 2);
+    }
+
+    #[test]
+    fn storms_break_trees() {
+        let mut game = HeadlessGame::new();
+        game.place(WorldObject::Tree, (5, 5));
+        game.app
+            .insert_resource(WeatherState::steady(Weather::Storm));
+
+        // Every long frame is bound to hit the only tree
+        for _ in 0..3 {
+            game.skip(120.0);
+        }
+        assert_eq!(game.object_at((5, 5), Layer::Object), None);
+        assert_eq!(
+            game.object_at((5, 5), Layer::Overlay),
+            Some(WorldObject::Item(InventoryObject::Wood))
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{place_spawned, spawn_grass_tile, spawn_rock, spawn_tree, Grid, WorldObject, TILE};
+
+// Seed the world is generated from, set WORLD_SEED to reproduce a map
+#[derive(Resource, Clone, Copy)]
+pub struct WorldSeed(pub u64);
+
+// Single RNG shared by all generators, so they draw from one sequence
+#[derive(Resource)]
+pub struct WorldRng(pub StdRng);
+
+#[derive(Resource)]
+pub struct WorldGenConfig {
+    // Half of the generated square's side
+    pub size: f32,
+    // Half of the square around spawn kept free of trees and rocks
+    pub spawn_clearing: f32,
+    // Fractions of the tiles covered by each object
+    pub tree_density: f32,
+    pub rock_density: f32,
+    pub grass_density: f32,
+}
+
+impl Default for WorldGenConfig {
+    fn default() -> Self {
+        Self {
+            size: 700.0,
+            spawn_clearing: 100.0,
+            tree_density: 0.025,
+            rock_density: 0.025,
+            grass_density: 0.025,
+        }
+    }
+}
+
+impl WorldGenConfig {
+    fn count(&self, density: f32) -> usize {
+        let tiles_per_side = (self.size * 2.0 / TILE).floor();
+        (tiles_per_side * tiles_per_side * density).round() as usize
+    }
+}
+
+pub struct WorldGenPlugin;
+impl Plugin for WorldGenPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = std::env::var("WORLD_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or_else(|| rand::thread_rng().gen());
+
+        app.insert_resource(WorldSeed(seed))
+            .init_resource::<WorldGenConfig>()
+            .add_systems(Startup, seed_world_rng)
+            // Generators share the RNG, so their order has to be fixed
+            .add_systems(PostStartup, (spawn_trees, spawn_rocks, spawn_grass).chain());
+    }
+}
+
+fn seed_world_rng(mut commands: Commands, seed: Res<WorldSeed>) {
+    info!("Generating world from seed {}", seed.0);
+    commands.insert_resource(WorldRng(StdRng::seed_from_u64(seed.0)));
+}
+
+// Picks a random free tile, None when no free tile turned up
+fn random_free_tile(
+    rng: &mut StdRng,
+    grid: &Grid,
+    config: &WorldGenConfig,
+    keep_spawn_clear: bool,
+) -> Option<Vec2> {
+    for _ in 0..1000 {
+        let x = rng.gen_range(-config.size..config.size);
+        let y = rng.gen_range(-config.size..config.size);
+        // Don't spawn near player
+        if keep_spawn_clear && x.abs() < config.spawn_clearing && y.abs() < config.spawn_clearing {
+            continue;
+        }
+
+        let vec = Vec2::new(x, y);
+        if grid.is_free(vec) {
+            return Some(grid.grid_to_world(grid.world_to_grid(vec)));
+        }
+    }
+    None
+}
+
+fn spawn_trees(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut grid_query: Query<&mut Grid>,
+    mut rng: ResMut<WorldRng>,
+    config: Res<WorldGenConfig>,
+) {
+    let mut grid = grid_query.single_mut();
+    for _ in 0..config.count(config.tree_density) {
+        let Some(vec) = random_free_tile(&mut rng.0, &grid, &config, true) else {
+            break;
+        };
+        let id = spawn_tree(vec.extend(0.0), &mut commands, &asset_server);
+        place_spawned(id, vec, WorldObject::Tree, &mut commands, &mut grid);
+    }
+}
+
+fn spawn_rocks(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut grid_query: Query<&mut Grid>,
+    mut rng: ResMut<WorldRng>,
+    config: Res<WorldGenConfig>,
+) {
+    let mut grid = grid_query.single_mut();
+    for _ in 0..config.count(config.rock_density) {
+        let Some(vec) = random_free_tile(&mut rng.0, &grid, &config, true) else {
+            break;
+        };
+        let id = spawn_rock(vec.extend(0.0), &mut commands, &asset_server);
+        place_spawned(id, vec, WorldObject::Rock, &mut commands, &mut grid);
+    }
+}
+
+fn spawn_grass(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut grid_query: Query<&mut Grid>,
+    mut rng: ResMut<WorldRng>,
+    config: Res<WorldGenConfig>,
+) {
+    let mut grid = grid_query.single_mut();
+    for _ in 0..config.count(config.grass_density) {
+        let Some(vec) = random_free_tile(&mut rng.0, &grid, &config, false) else {
+            break;
+        };
+        let id = spawn_grass_tile(vec.extend(0.0), &mut commands, &asset_server);
+        place_spawned(id, vec, WorldObject::Grass, &mut commands, &mut grid);
+    }
+}